@@ -0,0 +1,616 @@
+use clap::{Parser, ValueEnum};
+use jetstream_client::config::{
+    AccountDataFilter, AccountFilter, AuthConfig, BackpressureConfig, BackpressurePolicy,
+    FilterConfig, KeepaliveConfig, LamportsFilter, MemcmpFilter, ReconnectConfig, TlsConfig,
+};
+use jetstream_client::connector::auth::TokenSource;
+use jetstream_client::decoder::lookup_tables::LOOKUP_TABLE_PROGRAM_ID;
+use jetstream_client::decoder::registry::DecoderRegistry;
+use jetstream_client::record::RecordHeader;
+use jetstream_client::replay::{capture_files, ReplaySpeed};
+use jetstream_client::sink::{RotatingFileSink, RotationConfig};
+use jetstream_client::JetstreamClient;
+use jetstream_protos::jetstream::{
+    SubscribeRequest, SubscribeRequestFilterAccounts, SubscribeRequestFilterTransactions,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::time::Duration;
+
+use crate::writer::{OutputWriter, RecordFormat};
+
+/// Name of the account filter built from the --watch-* options
+const CLI_ACCOUNTS_FILTER: &str = "cli-accounts";
+
+/// Command-line arguments
+#[derive(Parser, Debug, Clone)]
+#[command(name = "jetstream-example")]
+#[command(about = "JetStream Example Client", long_about = None)]
+pub struct ClientConfig {
+    /// Jetstream gRPC URL(s), comma-separated to race several endpoints
+    #[arg(
+        short = 'j',
+        long,
+        value_delimiter = ',',
+        default_value = "PLACE_URL_HERE"
+    )]
+    pub jetstream_grpc_url: Vec<String>,
+
+    /// X token
+    #[arg(short = 'x', long, conflicts_with_all = ["x_token_file", "x_token_env"])]
+    pub x_token: Option<String>,
+
+    /// Read the token from a file (re-read every --auth-refresh-secs)
+    #[arg(long, conflicts_with = "x_token_env")]
+    pub x_token_file: Option<PathBuf>,
+
+    /// Read the token from an environment variable (re-read every --auth-refresh-secs)
+    #[arg(long)]
+    pub x_token_env: Option<String>,
+
+    /// Metadata header carrying the token [default: authorization, or x-token with --parsed-enabled]
+    #[arg(long)]
+    pub auth_header: Option<String>,
+
+    /// Prefix the token with "Bearer "
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub auth_bearer: bool,
+
+    /// Interval for re-reading file or env tokens, in seconds (0 disables refresh)
+    #[arg(long, default_value_t = 60)]
+    pub auth_refresh_secs: u64,
+
+    /// PEM bundle of additional CA certificates trusted for https:// endpoints
+    #[arg(long)]
+    pub tls_ca_cert: Option<PathBuf>,
+
+    /// Do not trust the system root certificates
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub tls_no_system_roots: bool,
+
+    /// PEM client certificate for mutual TLS
+    #[arg(long, requires = "tls_client_key")]
+    pub tls_client_cert: Option<PathBuf>,
+
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1) for the mutual TLS client certificate
+    #[arg(long, requires = "tls_client_cert")]
+    pub tls_client_key: Option<PathBuf>,
+
+    /// Override the server name used for SNI and certificate verification
+    #[arg(long)]
+    pub tls_domain_name: Option<String>,
+
+    /// Timeout for establishing a connection, in seconds
+    #[arg(long)]
+    pub connect_timeout_secs: Option<u64>,
+
+    /// Filter config file path (JSON format)
+    #[arg(short = 'f', long)]
+    pub filter_config_path: Option<PathBuf>,
+
+    /// Include accounts (comma-separated Solana pubkeys)
+    #[arg(short = 'i', long, value_delimiter = ',')]
+    pub include_accounts: Option<Vec<String>>,
+
+    /// Exclude accounts (comma-separated Solana pubkeys)
+    #[arg(short = 'e', long, value_delimiter = ',')]
+    pub exclude_accounts: Option<Vec<String>>,
+
+    /// Required accounts (comma-separated Solana pubkeys)
+    #[arg(short = 'r', long, value_delimiter = ',')]
+    pub required_accounts: Option<Vec<String>>,
+
+    /// Watch accounts (comma-separated Solana pubkeys)
+    #[arg(long, value_delimiter = ',')]
+    pub watch_accounts: Option<Vec<String>>,
+
+    /// Watch accounts owned by these programs (comma-separated Solana pubkeys)
+    #[arg(long, value_delimiter = ',')]
+    pub watch_owners: Option<Vec<String>>,
+
+    /// Only watch accounts with this data size
+    #[arg(long)]
+    pub watch_datasize: Option<u64>,
+
+    /// Only watch accounts whose data matches OFFSET:BASE58 (repeatable)
+    #[arg(long)]
+    pub watch_memcmp: Vec<MemcmpFilter>,
+
+    /// Only watch accounts whose lamports match OP:VALUE, OP being eq, ne, lt or gt
+    #[arg(long)]
+    pub watch_lamports: Option<LamportsFilter>,
+
+    /// Subscribe to address lookup table accounts and resolve v0 transaction lookups
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub resolve_lookup_tables: bool,
+
+    /// Only track these lookup tables (comma-separated Solana pubkeys) instead of all of them
+    #[arg(long, value_delimiter = ',', requires = "resolve_lookup_tables")]
+    pub lookup_tables: Option<Vec<String>>,
+
+    /// Verify the ed25519 signatures of every transaction and warn about invalid ones
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub verify_signatures: bool,
+
+    /// Output format: log lines, one JSON object per transaction, length-delimited protobuf
+    /// updates as received from every endpoint, or a replayable capture (those updates after a header)
+    #[arg(long, value_enum, default_value_t = OutputFormat::Log)]
+    pub output: OutputFormat,
+
+    /// Write --output jsonl, protobuf or record to this file instead of stdout
+    #[arg(long, conflicts_with = "output_dir")]
+    pub output_file: Option<PathBuf>,
+
+    /// Write --output jsonl, protobuf or record to rotating segment files in this directory
+    #[arg(long)]
+    pub output_dir: Option<PathBuf>,
+
+    /// Start a new segment once the current one holds this many bytes
+    #[arg(long, requires = "output_dir")]
+    pub rotate_max_bytes: Option<u64>,
+
+    /// Start a new segment at every multiple of this many seconds of wall-clock time
+    #[arg(long, requires = "output_dir")]
+    pub rotate_interval_secs: Option<u64>,
+
+    /// Start a new segment at every multiple of this many slots
+    #[arg(long, requires = "output_dir")]
+    pub rotate_slots: Option<u64>,
+
+    /// Compress closed segments with zstd
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "output_dir")]
+    pub compress_segments: bool,
+
+    /// Keep at most this many closed segments, deleting the oldest
+    #[arg(long, requires = "output_dir")]
+    pub retain_segments: Option<usize>,
+
+    /// Keep at most this many bytes of closed segments, deleting the oldest
+    #[arg(long, requires = "output_dir")]
+    pub retain_bytes: Option<u64>,
+
+    /// Enable parsed instruction streaming
+    #[arg(short = 'p', long, action = clap::ArgAction::SetTrue)]
+    pub parsed_enabled: bool,
+
+    /// Replay captured updates from these files or directories (comma-separated)
+    /// instead of connecting
+    #[arg(long, value_delimiter = ',', conflicts_with = "parsed_enabled")]
+    pub replay: Option<Vec<PathBuf>>,
+
+    /// Replay pace: max, realtime, or a multiple of the recorded pace such as 2 or 0.5
+    #[arg(long, default_value = "max", requires = "replay")]
+    pub replay_speed: ReplaySpeed,
+
+    /// Number of updates decoded in parallel; output keeps arrival order
+    #[arg(long, default_value_t = 1)]
+    pub decode_concurrency: usize,
+
+    /// Number of updates buffered before the backpressure policy applies
+    #[arg(long, default_value_t = 10_000)]
+    pub buffer_capacity: usize,
+
+    /// What to do with updates when the buffer is full
+    #[arg(long, value_enum, default_value_t = Backpressure::DropNewest)]
+    pub backpressure: Backpressure,
+
+    /// Directory for spill files with --backpressure spill-to-disk [default: system temp dir]
+    #[arg(long)]
+    pub spill_dir: Option<PathBuf>,
+
+    /// Minimum interval between warnings about dropped updates, in seconds
+    #[arg(long, default_value_t = 10)]
+    pub backpressure_warn_interval_secs: u64,
+
+    /// Maximum consecutive reconnect attempts (0 disables reconnects, unlimited if unset)
+    #[arg(long)]
+    pub reconnect_max_attempts: Option<u32>,
+
+    /// Initial delay before reconnecting, in milliseconds
+    #[arg(long, default_value_t = 500)]
+    pub reconnect_initial_backoff_ms: u64,
+
+    /// Upper bound for the reconnect delay, in milliseconds
+    #[arg(long, default_value_t = 30_000)]
+    pub reconnect_max_backoff_ms: u64,
+
+    /// Multiplier applied to the reconnect delay after each failed attempt
+    #[arg(long, default_value_t = 2.0)]
+    pub reconnect_backoff_multiplier: f64,
+
+    /// Random jitter applied to the reconnect delay (fraction, 0.0 - 1.0)
+    #[arg(long, default_value_t = 0.2)]
+    pub reconnect_jitter: f64,
+
+    /// Interval between client pings on the subscribe stream, in seconds (0 disables pings)
+    #[arg(long, default_value_t = 15)]
+    pub keepalive_interval_secs: u64,
+
+    /// Consecutive unanswered pings before the connection is considered dead
+    #[arg(long, default_value_t = 3)]
+    pub keepalive_max_missed: u32,
+
+    /// Number of recent signatures remembered to deduplicate raced endpoints
+    #[arg(long, default_value_t = 100_000)]
+    pub dedup_capacity: usize,
+
+    /// Interval between per-endpoint race statistics logs, in seconds
+    #[arg(long, default_value_t = 60)]
+    pub race_stats_interval_secs: u64,
+}
+
+/// Backpressure policy names accepted on the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backpressure {
+    Block,
+    DropNewest,
+    DropOldest,
+    SpillToDisk,
+}
+
+/// Output formats accepted on the command line
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Log,
+    Jsonl,
+    Protobuf,
+    Record,
+}
+
+impl ClientConfig {
+    /// --auth-header, defaulting to the header each connector has always sent
+    fn auth_header_name(&self) -> String {
+        match &self.auth_header {
+            Some(header) => header.clone(),
+            None if self.parsed_enabled => "x-token".to_string(),
+            None => "authorization".to_string(),
+        }
+    }
+
+    /// Build a client from the command-line settings
+    pub fn build_client(&self) -> anyhow::Result<JetstreamClient> {
+        let (transactions, accounts) = self.filters()?;
+
+        let token = if let Some(path) = &self.x_token_file {
+            TokenSource::File(path.clone())
+        } else if let Some(var) = &self.x_token_env {
+            TokenSource::Env(var.clone())
+        } else {
+            match &self.x_token {
+                Some(token) if !token.is_empty() => TokenSource::Static(token.clone()),
+                _ => TokenSource::None,
+            }
+        };
+
+        let mut builder = JetstreamClient::builder()
+            .endpoints(self.jetstream_grpc_url.iter())
+            .auth(AuthConfig {
+                token,
+                header: self.auth_header_name(),
+                bearer: self.auth_bearer,
+                refresh_interval: Duration::from_secs(self.auth_refresh_secs),
+            })
+            .tls(TlsConfig {
+                ca_cert: self.tls_ca_cert.clone(),
+                no_system_roots: self.tls_no_system_roots,
+                client_cert: self.tls_client_cert.clone(),
+                client_key: self.tls_client_key.clone(),
+                domain_name: self.tls_domain_name.clone(),
+            })
+            .reconnect(ReconnectConfig {
+                max_attempts: self.reconnect_max_attempts,
+                initial_backoff: Duration::from_millis(self.reconnect_initial_backoff_ms),
+                max_backoff: Duration::from_millis(self.reconnect_max_backoff_ms),
+                multiplier: self.reconnect_backoff_multiplier,
+                jitter: self.reconnect_jitter,
+            })
+            .keepalive(KeepaliveConfig {
+                interval: Duration::from_secs(self.keepalive_interval_secs),
+                max_missed: self.keepalive_max_missed,
+            })
+            .backpressure(BackpressureConfig {
+                policy: self.backpressure_policy(),
+                warn_interval: Duration::from_secs(self.backpressure_warn_interval_secs),
+            })
+            .channel_capacity(self.buffer_capacity)
+            .dedup_capacity(self.dedup_capacity)
+            .race_stats_interval(Duration::from_secs(self.race_stats_interval_secs))
+            .transaction_filters(transactions)
+            .account_filters(accounts);
+
+        if let Some(secs) = self.connect_timeout_secs {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+
+        builder.build()
+    }
+
+    fn backpressure_policy(&self) -> BackpressurePolicy {
+        match self.backpressure {
+            Backpressure::Block => BackpressurePolicy::Block,
+            Backpressure::DropNewest => BackpressurePolicy::DropNewest,
+            Backpressure::DropOldest => BackpressurePolicy::DropOldest,
+            Backpressure::SpillToDisk => BackpressurePolicy::SpillToDisk {
+                dir: self.spill_dir.clone().unwrap_or_else(std::env::temp_dir),
+            },
+        }
+    }
+
+    /// Record writer for --output jsonl or protobuf, `None` when logging
+    pub fn output_writer(&self) -> anyhow::Result<Option<OutputWriter>> {
+        let format = match self.output {
+            OutputFormat::Log => return Ok(None),
+            OutputFormat::Jsonl => RecordFormat::Jsonl,
+            OutputFormat::Protobuf => RecordFormat::Protobuf,
+            OutputFormat::Record => RecordFormat::Record,
+        };
+        let header = match format {
+            RecordFormat::Record => self.record_header()?.to_prefix(),
+            _ => Vec::new(),
+        };
+        let Some(dir) = &self.output_dir else {
+            return OutputWriter::stream(format, self.output_file.as_deref(), &header).map(Some);
+        };
+
+        let rotation = RotationConfig {
+            max_bytes: self.rotate_max_bytes,
+            interval: self.rotate_interval_secs.map(Duration::from_secs),
+            slot_interval: self.rotate_slots,
+            compress: self.compress_segments,
+            max_segments: self.retain_segments,
+            max_total_bytes: self.retain_bytes,
+        };
+        let prefix = if self.parsed_enabled {
+            "parsed"
+        } else {
+            "updates"
+        };
+        let sink = RotatingFileSink::new(dir, prefix, format.extension(), rotation)?;
+        Ok(Some(OutputWriter::segments(format, sink, header)))
+    }
+
+    /// Header of a capture of this subscription
+    fn record_header(&self) -> anyhow::Result<RecordHeader> {
+        let request = if self.parsed_enabled {
+            None
+        } else {
+            let (transactions, accounts) = self.filters()?;
+            Some(SubscribeRequest {
+                transactions,
+                accounts,
+                ping: None,
+            })
+        };
+        Ok(RecordHeader::new(
+            self.jetstream_grpc_url.clone(),
+            request,
+            self.parsed_enabled,
+        ))
+    }
+
+    /// Capture files behind --replay, `None` when connecting to Jetstream
+    pub fn replay_files(&self) -> anyhow::Result<Option<Vec<PathBuf>>> {
+        let Some(paths) = &self.replay else {
+            return Ok(None);
+        };
+        if matches!(self.output, OutputFormat::Protobuf | OutputFormat::Record) {
+            return Err(anyhow::anyhow!(
+                "--replay cannot re-record updates; use --output log or jsonl"
+            ));
+        }
+        let mut files = Vec::new();
+        for path in paths {
+            files.extend(capture_files(path)?);
+        }
+        if files.is_empty() {
+            return Err(anyhow::anyhow!("No capture files to replay"));
+        }
+        Ok(Some(files))
+    }
+
+    /// Built-in decoders plus one per IDL listed in the filter file
+    pub fn decoders(&self) -> anyhow::Result<DecoderRegistry> {
+        let mut decoders = DecoderRegistry::with_defaults();
+        if let Some(path) = &self.filter_config_path {
+            FilterConfig::from_file(path)?.register_idls(&mut decoders)?;
+        }
+        Ok(decoders)
+    }
+
+    /// Transaction and account filters from the filter file or command-line options
+    pub fn filters(
+        &self,
+    ) -> anyhow::Result<(
+        HashMap<String, SubscribeRequestFilterTransactions>,
+        HashMap<String, SubscribeRequestFilterAccounts>,
+    )> {
+        let filter_config = self
+            .filter_config_path
+            .as_deref()
+            .map(FilterConfig::from_file)
+            .transpose()?;
+
+        Ok((
+            self.transaction_filters(filter_config.as_ref()),
+            self.account_filters(filter_config.as_ref())?,
+        ))
+    }
+
+    fn has_account_filters(&self) -> bool {
+        self.watch_accounts.is_some()
+            || self.watch_owners.is_some()
+            || self.watch_datasize.is_some()
+            || !self.watch_memcmp.is_empty()
+            || self.watch_lamports.is_some()
+    }
+
+    /// Build transaction filters from config
+    fn transaction_filters(
+        &self,
+        filter_config: Option<&FilterConfig>,
+    ) -> HashMap<String, SubscribeRequestFilterTransactions> {
+        let mut filters = HashMap::new();
+
+        // Use filters from file if specified
+        if let Some(filter_config) = filter_config {
+            filters = filter_config.transaction_filters();
+        }
+        // If no file but command-line filters provided, use those
+        else if self.include_accounts.is_some()
+            || self.exclude_accounts.is_some()
+            || self.required_accounts.is_some()
+        {
+            log::info!("Using command-line filter configuration");
+            filters.insert(
+                "cli-filter".to_string(),
+                SubscribeRequestFilterTransactions {
+                    account_include: self.include_accounts.clone().unwrap_or_default(),
+                    account_exclude: self.exclude_accounts.clone().unwrap_or_default(),
+                    account_required: self.required_accounts.clone().unwrap_or_default(),
+                },
+            );
+        }
+        // Only accounts are watched - no transaction filter
+        else if self.has_account_filters() {
+            log::info!("Only account filters specified, not subscribing to transactions");
+        }
+        // Default case - empty filter (all transactions)
+        else {
+            log::info!("No filters specified, using default filter (all transactions)");
+            filters.insert(
+                "default".to_string(),
+                SubscribeRequestFilterTransactions {
+                    account_include: vec![],
+                    account_exclude: vec![],
+                    account_required: vec![],
+                },
+            );
+        }
+
+        filters
+    }
+
+    /// Build account filters from config; --watch-* options add a `cli-accounts`
+    /// filter next to the ones from the filter file
+    fn account_filters(
+        &self,
+        filter_config: Option<&FilterConfig>,
+    ) -> anyhow::Result<HashMap<String, SubscribeRequestFilterAccounts>> {
+        let mut accounts = match filter_config {
+            Some(filter_config) => filter_config.account_filters()?,
+            None => HashMap::new(),
+        };
+
+        if self.has_account_filters() {
+            if accounts.contains_key(CLI_ACCOUNTS_FILTER) {
+                return Err(anyhow::anyhow!(
+                    "Account filter '{}' of the filter file clashes with the --watch-* options",
+                    CLI_ACCOUNTS_FILTER
+                ));
+            }
+            log::info!("Using command-line account filter configuration");
+            let mut filters = Vec::new();
+            if let Some(datasize) = self.watch_datasize {
+                filters.push(AccountDataFilter::Datasize(datasize));
+            }
+            for memcmp in &self.watch_memcmp {
+                filters.push(AccountDataFilter::Memcmp(memcmp.clone()));
+            }
+            if let Some(lamports) = &self.watch_lamports {
+                filters.push(AccountDataFilter::Lamports(lamports.clone()));
+            }
+
+            let filter = AccountFilter {
+                account: self.watch_accounts.clone().unwrap_or_default(),
+                owner: self.watch_owners.clone().unwrap_or_default(),
+                filters,
+            };
+            accounts.insert(CLI_ACCOUNTS_FILTER.to_string(), (&filter).try_into()?);
+        }
+
+        if self.resolve_lookup_tables {
+            let filter = match &self.lookup_tables {
+                Some(tables) => SubscribeRequestFilterAccounts {
+                    account: tables.clone(),
+                    ..Default::default()
+                },
+                None => SubscribeRequestFilterAccounts {
+                    owner: vec![LOOKUP_TABLE_PROGRAM_ID.to_string()],
+                    ..Default::default()
+                },
+            };
+            accounts.insert("lookup-tables".to_string(), filter);
+        }
+
+        Ok(accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBKEY: &str = "11111111111111111111111111111111";
+
+    fn config(args: &[&str]) -> ClientConfig {
+        ClientConfig::parse_from(["jetstream-example"].iter().chain(args))
+    }
+
+    #[test]
+    fn auth_header_defaults_per_connector() {
+        assert_eq!(config(&[]).auth_header_name(), "authorization");
+        assert_eq!(config(&["-p"]).auth_header_name(), "x-token");
+        let custom = config(&["-p", "--auth-header", "authorization"]);
+        assert_eq!(custom.auth_header_name(), "authorization");
+    }
+
+    #[test]
+    fn any_watch_option_subscribes_to_accounts() {
+        for args in [
+            &["--watch-datasize", "165"][..],
+            &["--watch-memcmp", "0:2"],
+            &["--watch-lamports", "gt:0"],
+        ] {
+            let (transactions, accounts) = config(args).filters().unwrap();
+            assert!(transactions.is_empty(), "{:?}", args);
+            assert_eq!(accounts[CLI_ACCOUNTS_FILTER].filters.len(), 1, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn watch_options_are_merged_with_filter_file() {
+        let path = std::env::temp_dir().join(format!("filters-{}.json", std::process::id()));
+        let file = format!(r#"{{"accounts": {{"file": {{"owner": ["{}"]}}}}}}"#, PUBKEY);
+        std::fs::write(&path, file).unwrap();
+
+        let (_, accounts) = config(&[
+            "-f",
+            path.to_str().unwrap(),
+            "--watch-accounts",
+            PUBKEY,
+            "--watch-datasize",
+            "82",
+        ])
+        .filters()
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(accounts["file"].owner, [PUBKEY]);
+        assert_eq!(accounts[CLI_ACCOUNTS_FILTER].account, [PUBKEY]);
+        assert_eq!(accounts[CLI_ACCOUNTS_FILTER].filters.len(), 1);
+    }
+
+    #[test]
+    fn clashing_filter_file_name_is_rejected() {
+        let path = std::env::temp_dir().join(format!("clash-{}.json", std::process::id()));
+        let file = format!(
+            r#"{{"accounts": {{"cli-accounts": {{"account": ["{}"]}}}}}}"#,
+            PUBKEY
+        );
+        std::fs::write(&path, file).unwrap();
+
+        let result = config(&["-f", path.to_str().unwrap(), "--watch-owners", PUBKEY]).filters();
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -1,3 +0,0 @@
-pub mod config;
-pub mod connector;
-pub mod parsed;
@@ -0,0 +1,125 @@
+use jetstream_client::decoder::pumpfun::{PumpProgramIx, PUMPFUN_PROGRAM_ID};
+use jetstream_client::decoder::registry::DecoderRegistry;
+use jetstream_protos::jetstream::{
+    SubscribeUpdateParsedTransaction, SubscribeUpdateTransactionInfo,
+};
+use serde_json::{json, Value};
+use solana_sdk::bs58;
+use std::time::SystemTime;
+
+use crate::output::DecodedUpdate;
+
+/// `{"signature", "slot", "created_at", "filters", "account_keys", "instructions", ...}`;
+/// each instruction carries its program id, accounts and base58 data, plus the
+/// decoder name and args when a decoder understood it
+pub fn transaction_json(
+    tx_info: &SubscribeUpdateTransactionInfo,
+    decoded: &DecodedUpdate,
+    decoders: &DecoderRegistry,
+) -> Value {
+    let accounts = &decoded.accounts;
+    let instructions: Vec<Value> = tx_info
+        .instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            let mut value = json!({
+                "program_id": accounts.program_id(instruction).map(|key| key.to_string()),
+                "accounts": instruction
+                    .accounts
+                    .iter()
+                    .map(|i| accounts.get(*i as usize).map(|key| key.to_string()))
+                    .collect::<Vec<_>>(),
+                "data": bs58::encode(&instruction.data).into_string(),
+            });
+            let Some(decoded_ix) = decoded.instructions.iter().find(|ix| ix.index == index) else {
+                return value;
+            };
+            value["decoder"] = decoders
+                .get(&decoded_ix.program_id)
+                .map(|decoder| Value::from(decoder.name()))
+                .unwrap_or(Value::Null);
+            value["args"] = decoded_ix.instruction.args_json().unwrap_or_else(|e| {
+                log::debug!("Failed to serialize instruction #{} args: {}", index, e);
+                Value::Null
+            });
+            value
+        })
+        .collect();
+
+    let mut value = json!({
+        "signature": bs58::encode(&tx_info.signature).into_string(),
+        "slot": tx_info.slot,
+        "created_at": decoded.update.created_at.as_ref().map(|t| t.to_string()),
+        "filters": decoded.update.filters,
+        "account_keys": accounts.keys().iter().map(|key| key.to_string()).collect::<Vec<_>>(),
+        "instructions": instructions,
+    });
+    if let Some(budget) = &decoded.compute_budget {
+        value["priority_fee_lamports"] = budget.priority_fee_lamports().into();
+    }
+    if let Some(reason) = &decoded.unresolved {
+        value["unresolved"] = reason.as_str().into();
+    }
+    value
+}
+
+/// Raw transaction shape with the pump.fun args decoded by the server. The parsed
+/// stream carries no account keys nor creation time, so `received_at` holds the local
+/// receive time in place of the server's `created_at`.
+pub fn parsed_transaction_json(parsed_tx: &SubscribeUpdateParsedTransaction) -> Value {
+    let instructions: Vec<Value> = parsed_tx
+        .instructions
+        .iter()
+        .map(|instruction| {
+            let args = PumpProgramIx::from_parsed(instruction)
+                .map_err(anyhow::Error::from)
+                .and_then(|ix| Ok(serde_json::to_value(ix)?))
+                .unwrap_or_else(|e| {
+                    log::debug!("Failed to convert parsed instruction: {:#}", e);
+                    Value::Null
+                });
+            json!({
+                "program_id": PUMPFUN_PROGRAM_ID.to_string(),
+                "decoder": "pump.fun",
+                "args": args,
+            })
+        })
+        .collect();
+
+    json!({
+        "signature": bs58::encode(&parsed_tx.signature).into_string(),
+        "slot": parsed_tx.slot,
+        "received_at": prost_types::Timestamp::from(SystemTime::now()).to_string(),
+        "signatures": parsed_tx
+            .signatures
+            .iter()
+            .map(|signature| bs58::encode(signature).into_string())
+            .collect::<Vec<_>>(),
+        "recent_blockhash": bs58::encode(&parsed_tx.recent_blockhash).into_string(),
+        "account": parsed_tx
+            .account
+            .as_ref()
+            .and_then(|update| update.account.as_ref())
+            .map(|account| bs58::encode(&account.pubkey).into_string()),
+        "instructions": instructions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsed_transactions_carry_the_receive_time() {
+        let parsed_tx = SubscribeUpdateParsedTransaction {
+            signature: vec![7; 64],
+            slot: 42,
+            ..Default::default()
+        };
+        let value = parsed_transaction_json(&parsed_tx);
+        assert!(value.get("created_at").is_none());
+        assert!(value["received_at"].is_string());
+        assert_eq!(value["slot"], 42);
+    }
+}
@@ -1,10 +1,22 @@
-mod connector;
+mod config;
+mod jsonl;
+mod output;
+mod writer;
+
 use clap::Parser;
 use env_logger::Env;
-pub mod decoder;
+use jetstream_client::decoder::lookup_tables::LookupTableResolver;
+use jetstream_client::replay::replay;
+use jetstream_client::{RawUpdate, UpdateStream};
 
-use connector::config::ClientConfig;
-use tokio::sync::watch;
+use config::ClientConfig;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{mpsc, watch};
+use tokio_stream::StreamExt;
+use writer::OutputWriter;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -31,30 +43,171 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
-    if config.parsed_enabled {
-        connector::parsed::jetstream_parsed_connector(config, shutdown_rx).await?;
+    let mut writer = config.output_writer()?;
+
+    let dropped = if config.parsed_enabled {
+        let client = config.build_client()?;
+        // Captures record the transactions exactly as received
+        let (mut stream, mut tap) = match writer.as_ref() {
+            Some(writer) if writer.captures_raw() => {
+                let (stream, tap) = client.subscribe_parsed_with_tap(config.buffer_capacity);
+                (stream, Some(tap))
+            }
+            _ => (client.subscribe_parsed(), None),
+        };
+        let dropped = stream.dropped_counter();
+        loop {
+            tokio::select! {
+                update = stream.next() => {
+                    match update {
+                        Some(Ok(parsed_tx)) => match writer.as_mut() {
+                            Some(_) if tap.is_some() => {}
+                            Some(writer) => writer.write_parsed_transaction(&parsed_tx)?,
+                            None => output::log_parsed_transaction(&parsed_tx),
+                        },
+                        Some(Err(e)) => return Err(e),
+                        None => break,
+                    }
+                }
+                Some(raw) = recv_tap(&mut tap) => {
+                    if let Some(writer) = writer.as_mut() {
+                        writer.write_raw(&raw)?;
+                    }
+                }
+                Ok(()) = shutdown_rx.changed() => {
+                    if *shutdown_rx.borrow() {
+                        log::info!("Jetstream parsed connector shutting down due to signal");
+                        break;
+                    }
+                }
+            }
+        }
+        if let (Some(tap), Some(writer)) = (tap.as_mut(), writer.as_mut()) {
+            while let Ok(raw) = tap.try_recv() {
+                writer.write_raw(&raw)?;
+            }
+        }
+        dropped
+    } else if let Some(files) = config.replay_files()? {
+        let stream = replay(files, config.replay_speed);
+        process_updates(stream, None, &config, writer.as_mut(), &mut shutdown_rx).await?
     } else {
-        let mut connector_handle = tokio::spawn(async move {
-            connector::connector::jetstream_connector(config).await
-        });
+        let client = config.build_client()?;
+        #[cfg(unix)]
+        {
+            let config = config.clone();
+            let subscription = client.subscription().clone();
+            tokio::spawn(async move {
+                let mut hangup = match signal(SignalKind::hangup()) {
+                    Ok(hangup) => hangup,
+                    Err(err) => {
+                        log::warn!("Error setting up SIGHUP handler: {}", err);
+                        return;
+                    }
+                };
+                while hangup.recv().await.is_some() {
+                    log::info!("Received SIGHUP, reloading filters...");
+                    let result = match config.filters() {
+                        Ok((transactions, accounts)) => {
+                            subscription.update_filters(transactions, accounts).await
+                        }
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = result {
+                        log::warn!("Failed to reload filters: {:#}", e);
+                    }
+                }
+            });
+        }
+
+        // Captures record the messages exactly as received, before dedup
+        let (stream, tap) = match writer.as_ref() {
+            Some(writer) if writer.captures_raw() => {
+                let (stream, tap) = client.subscribe_with_tap(config.buffer_capacity);
+                (stream, Some(tap))
+            }
+            _ => (client.subscribe(), None),
+        };
+        process_updates(stream, tap, &config, writer.as_mut(), &mut shutdown_rx).await?
+    };
 
+    if let Some(writer) = writer.as_mut() {
+        writer.close()?;
+    }
+
+    let dropped = dropped.load(Ordering::Relaxed);
+    if dropped > 0 {
+        log::warn!("{} update(s) were dropped due to backpressure", dropped);
+    }
+
+    Ok(())
+}
+
+/// Decode and output a raw update stream until it ends or shutdown is signalled,
+/// returning its dropped-update counter. With a `tap`, the writer records the
+/// tapped messages instead of the decoded updates.
+async fn process_updates(
+    stream: UpdateStream,
+    mut tap: Option<mpsc::Receiver<RawUpdate>>,
+    config: &ClientConfig,
+    mut writer: Option<&mut OutputWriter>,
+    shutdown_rx: &mut watch::Receiver<bool>,
+) -> anyhow::Result<Arc<AtomicU64>> {
+    let dropped = stream.dropped_counter();
+    let decoders = config.decoders()?;
+    let names = decoders.clone();
+    let resolver = config.resolve_lookup_tables.then(LookupTableResolver::new);
+    let verify_signatures = config.verify_signatures;
+    let tables = resolver.clone();
+    let stream = stream.decode_ordered_with(
+        config.decode_concurrency,
+        move |update| output::apply_update(update, tables.as_ref()),
+        move |update| {
+            output::decode_update(update, &decoders, resolver.as_ref(), verify_signatures)
+        },
+    );
+    let mut stream = std::pin::pin!(stream);
+    loop {
         tokio::select! {
-            result = &mut connector_handle => {
-                match result {
-                    Ok(Ok(())) => log::info!("Connector completed successfully"),
-                    Ok(Err(e)) => return Err(e),
-                    Err(e) => return Err(anyhow::anyhow!("Connector task panicked: {}", e)),
+            update = stream.next() => {
+                match update {
+                    Some(Ok(decoded)) => match writer.as_deref_mut() {
+                        Some(_) if tap.is_some() => {}
+                        Some(writer) => writer.write_update(&decoded, &names)?,
+                        None => output::log_update(&decoded),
+                    },
+                    Some(Err(e)) => return Err(e),
+                    None => {
+                        log::info!("Connector completed successfully");
+                        break;
+                    }
+                }
+            }
+            Some(raw) = recv_tap(&mut tap) => {
+                if let Some(writer) = writer.as_deref_mut() {
+                    writer.write_raw(&raw)?;
                 }
             }
             Ok(()) = shutdown_rx.changed() => {
                 if *shutdown_rx.borrow() {
-                    log::info!("Shutdown signal received, aborting connector...");
-                    connector_handle.abort();
-                    let _ = connector_handle.await;
+                    log::info!("Shutdown signal received, stopping connector...");
+                    break;
                 }
             }
         }
     }
+    if let (Some(tap), Some(writer)) = (tap.as_mut(), writer) {
+        while let Ok(raw) = tap.try_recv() {
+            writer.write_raw(&raw)?;
+        }
+    }
+    Ok(dropped)
+}
 
-    Ok(())
+/// Next tapped message, pending forever without a tap
+async fn recv_tap(tap: &mut Option<mpsc::Receiver<RawUpdate>>) -> Option<RawUpdate> {
+    match tap {
+        Some(tap) => tap.recv().await,
+        None => std::future::pending().await,
+    }
 }
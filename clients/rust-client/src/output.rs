@@ -0,0 +1,338 @@
+use jetstream_client::decoder::accounts::TransactionAccounts;
+use jetstream_client::decoder::compute_budget::ComputeBudget;
+use jetstream_client::decoder::lookup_tables::LookupTableResolver;
+use jetstream_client::decoder::pumpfun::PumpProgramKeys;
+use jetstream_client::decoder::raydium_amm::RaydiumAmmKeys;
+use jetstream_client::decoder::registry::{
+    DecodedInstruction, DecoderRegistry, TransactionInstruction,
+};
+use jetstream_client::decoder::transaction::versioned_transaction;
+use jetstream_client::{Update, UpdateKind};
+use jetstream_protos::jetstream::{
+    instruction::InstructionOneof, SubscribeUpdateAccount, SubscribeUpdateParsedTransaction,
+    SubscribeUpdateTransactionInfo,
+};
+use solana_sdk::bs58;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::message::v0::LoadedAddresses;
+
+/// Update with its account keys resolved and known program instructions decoded
+pub struct DecodedUpdate {
+    pub update: Update,
+    /// Static account keys followed by addresses loaded from lookup tables
+    pub accounts: TransactionAccounts,
+    /// Why the lookup table addresses could not be loaded, if they could not
+    pub unresolved: Option<String>,
+    pub instructions: Vec<TransactionInstruction>,
+    pub compute_budget: Option<ComputeBudget>,
+    /// Why the signatures failed verification, if they were verified and did
+    pub invalid_signature: Option<String>,
+}
+
+/// Apply an update to state later updates are decoded against, i.e. cache the
+/// lookup tables carried by account updates. Must run in stream order.
+pub fn apply_update(update: &Update, resolver: Option<&LookupTableResolver>) {
+    if let (UpdateKind::Account(account_update), Some(resolver)) = (&update.kind, resolver) {
+        resolver.update_from_account(account_update);
+    }
+}
+
+/// Resolve lookup tables and decode the instructions of a transaction update;
+/// account updates must have gone through [`apply_update`] first
+pub fn decode_update(
+    update: Update,
+    decoders: &DecoderRegistry,
+    resolver: Option<&LookupTableResolver>,
+    verify_signatures: bool,
+) -> DecodedUpdate {
+    let mut decoded = DecodedUpdate {
+        update,
+        accounts: TransactionAccounts::default(),
+        unresolved: None,
+        instructions: Vec::new(),
+        compute_budget: None,
+        invalid_signature: None,
+    };
+
+    match &decoded.update.kind {
+        UpdateKind::Transaction(tx_info) => {
+            if verify_signatures {
+                decoded.invalid_signature = versioned_transaction(tx_info, true)
+                    .err()
+                    .map(|e| format!("{:#}", e));
+            }
+            decoded.compute_budget = ComputeBudget::from_transaction(tx_info)
+                .inspect_err(|e| log::debug!("{:#}", e))
+                .ok();
+            let loaded = match resolver {
+                Some(resolver) if !tx_info.address_table_lookups.is_empty() => {
+                    resolver.resolve_cached(tx_info).unwrap_or_else(|e| {
+                        decoded.unresolved = Some(format!("{:#}", e));
+                        LoadedAddresses::default()
+                    })
+                }
+                _ => LoadedAddresses::default(),
+            };
+            decoded.accounts = match TransactionAccounts::new(tx_info, loaded) {
+                Ok(accounts) => accounts,
+                Err(e) => {
+                    decoded.unresolved = Some(format!("{:#}", e));
+                    return decoded;
+                }
+            };
+
+            decoded.instructions = decoders.decode_transaction(tx_info, &decoded.accounts);
+        }
+        UpdateKind::Account(_) => {}
+    }
+    decoded
+}
+
+pub fn log_update(decoded: &DecodedUpdate) {
+    match &decoded.update.kind {
+        UpdateKind::Transaction(tx_info) => log_transaction(tx_info, decoded),
+        UpdateKind::Account(account_update) => log_account(account_update),
+    }
+}
+
+fn log_transaction(tx_info: &SubscribeUpdateTransactionInfo, decoded: &DecodedUpdate) {
+    let signature = bs58::encode(&tx_info.signature).into_string();
+    log::info!(
+        "Jetstream - Transaction received - Signature: {}",
+        signature
+    );
+    if let Some(reason) = &decoded.invalid_signature {
+        log::warn!(
+            "Signature: {} - Signature verification failed: {}",
+            signature,
+            reason
+        );
+    }
+    if let Some(reason) = &decoded.unresolved {
+        log::debug!(
+            "Signature: {} - Account keys not fully resolved: {}",
+            signature,
+            reason
+        );
+    } else if !tx_info.address_table_lookups.is_empty() {
+        log::debug!(
+            "Signature: {} - {} account key(s), {} loaded from {} lookup table(s)",
+            signature,
+            decoded.accounts.keys().len(),
+            decoded.accounts.num_loaded(),
+            tx_info.address_table_lookups.len()
+        );
+    }
+    if let Some(budget) = &decoded.compute_budget {
+        log::debug!(
+            "Signature: {} - Compute unit limit: {}, price: {} micro-lamports, priority fee: {} lamports",
+            signature,
+            budget.effective_unit_limit(),
+            budget.unit_price_micro_lamports.unwrap_or(0),
+            budget.priority_fee_lamports()
+        );
+    }
+    for decoded_ix in &decoded.instructions {
+        let accounts: &[AccountMeta] = match &decoded_ix.instruction {
+            DecodedInstruction::PumpFun(ix) => {
+                log::info!("Signature: {} - Pump program ix: {:#?}", signature, ix.ix);
+                log_pump_keys(&signature, &ix.keys);
+                &ix.accounts
+            }
+            DecodedInstruction::RaydiumAmm(ix) => {
+                log::info!("Signature: {} - Raydium AMM ix: {:#?}", signature, ix.ix);
+                log_raydium_amm_keys(&signature, &ix.keys);
+                &ix.accounts
+            }
+            DecodedInstruction::SplToken(ix) => {
+                match ix.flow() {
+                    Some(flow) => log::info!(
+                        "Signature: {} - Token flow: {} {} -> {} (mint: {}, authority: {}{})",
+                        signature,
+                        flow.amount,
+                        flow.source
+                            .map_or_else(|| "mint".to_string(), |key| key.to_string()),
+                        flow.destination
+                            .map_or_else(|| "burn".to_string(), |key| key.to_string()),
+                        flow.mint
+                            .map_or_else(|| "unknown".to_string(), |key| key.to_string()),
+                        flow.authority,
+                        flow.fee
+                            .map_or_else(String::new, |fee| format!(", fee: {}", fee))
+                    ),
+                    None => log::debug!("Signature: {} - Token ix: {:?}", signature, ix.ix),
+                }
+                &ix.accounts
+            }
+            DecodedInstruction::System(ix) => {
+                if let Some((from, to, lamports)) = ix.lamports_transfer() {
+                    log::info!(
+                        "Signature: {} - SOL transfer: {} lamports {} -> {}",
+                        signature,
+                        lamports,
+                        from,
+                        to
+                    );
+                } else {
+                    log::debug!("Signature: {} - System ix: {:?}", signature, ix.ix);
+                }
+                &ix.accounts
+            }
+            DecodedInstruction::ComputeBudget(ix) => {
+                log::debug!("Signature: {} - Compute budget ix: {:?}", signature, ix);
+                &[]
+            }
+            DecodedInstruction::Anchor(ix) => {
+                log::info!(
+                    "Signature: {} - {} ix: {}",
+                    signature,
+                    ix.program,
+                    ix.to_json()
+                );
+                &ix.accounts
+            }
+            DecodedInstruction::Custom {
+                decoder,
+                value,
+                accounts,
+            } => {
+                log::info!(
+                    "Signature: {} - {} ix #{}: {}",
+                    signature,
+                    decoder,
+                    decoded_ix.index,
+                    value
+                );
+                accounts
+            }
+        };
+        for (index, meta) in accounts.iter().enumerate() {
+            log::debug!(
+                "Signature: {} -   #{} {}{}{}",
+                signature,
+                index,
+                meta.pubkey,
+                if meta.is_writable { " (writable)" } else { "" },
+                if meta.is_signer { " (signer)" } else { "" }
+            );
+        }
+    }
+}
+
+fn log_pump_keys(signature: &str, keys: &PumpProgramKeys) {
+    match keys {
+        PumpProgramKeys::Create(keys) => log::info!(
+            "Signature: {} - Create - Mint: {}, Bonding curve: {}, User: {}",
+            signature,
+            keys.mint,
+            keys.bonding_curve,
+            keys.user
+        ),
+        PumpProgramKeys::Buy(keys) => log::info!(
+            "Signature: {} - Buy - Mint: {}, Bonding curve: {}, User: {}, Fee recipient: {}",
+            signature,
+            keys.mint,
+            keys.bonding_curve,
+            keys.user,
+            keys.fee_recipient
+        ),
+        PumpProgramKeys::Sell(keys) => log::info!(
+            "Signature: {} - Sell - Mint: {}, Bonding curve: {}, User: {}, Fee recipient: {}",
+            signature,
+            keys.mint,
+            keys.bonding_curve,
+            keys.user,
+            keys.fee_recipient
+        ),
+        PumpProgramKeys::Withdraw(keys) => log::info!(
+            "Signature: {} - Withdraw - Mint: {}, Bonding curve: {}, User: {}",
+            signature,
+            keys.mint,
+            keys.bonding_curve,
+            keys.user
+        ),
+        _ => {}
+    }
+}
+
+fn log_raydium_amm_keys(signature: &str, keys: &RaydiumAmmKeys) {
+    match keys {
+        RaydiumAmmKeys::Initialize2(keys) => log::info!(
+            "Signature: {} - Initialize2 - AMM: {}, Coin mint: {}, PC mint: {}, User: {}",
+            signature,
+            keys.amm,
+            keys.coin_mint,
+            keys.pc_mint,
+            keys.user_wallet
+        ),
+        RaydiumAmmKeys::Deposit(keys) => log::info!(
+            "Signature: {} - Deposit - AMM: {}, User: {}",
+            signature,
+            keys.amm,
+            keys.user_owner
+        ),
+        RaydiumAmmKeys::Withdraw(keys) => log::info!(
+            "Signature: {} - Withdraw - AMM: {}, User: {}",
+            signature,
+            keys.amm,
+            keys.user_owner
+        ),
+        RaydiumAmmKeys::Swap(keys) => log::info!(
+            "Signature: {} - Swap - AMM: {}, User: {}, Source: {}, Destination: {}",
+            signature,
+            keys.amm,
+            keys.user_source_owner,
+            keys.user_source_token_account,
+            keys.user_destination_token_account
+        ),
+    }
+}
+
+fn log_account(account_update: &SubscribeUpdateAccount) {
+    let Some(account) = &account_update.account else {
+        return;
+    };
+    log::info!(
+        "Jetstream - Account update - Pubkey: {}, Owner: {}, Lamports: {}, Data: {} bytes, Slot: {}{}",
+        bs58::encode(&account.pubkey).into_string(),
+        bs58::encode(&account.owner).into_string(),
+        account.lamports,
+        account.data.len(),
+        account_update.slot,
+        if account_update.is_startup { " (startup)" } else { "" }
+    );
+}
+
+pub fn log_parsed_transaction(parsed_tx: &SubscribeUpdateParsedTransaction) {
+    if parsed_tx.signature.is_empty() {
+        return;
+    }
+    let signature = bs58::encode(&parsed_tx.signature).into_string();
+
+    // Extract and format instruction types for display only
+    let instruction_summary = if !parsed_tx.instructions.is_empty() {
+        let instruction_types: Vec<&str> = parsed_tx
+            .instructions
+            .iter()
+            .filter_map(|instruction| match &instruction.instruction_oneof {
+                Some(InstructionOneof::Initialize(_)) => Some("initialize"),
+                Some(InstructionOneof::SetParams(_)) => Some("set_params"),
+                Some(InstructionOneof::Create(_)) => Some("create"),
+                Some(InstructionOneof::Buy(_)) => Some("buy"),
+                Some(InstructionOneof::Sell(_)) => Some("sell"),
+                Some(InstructionOneof::Withdraw(_)) => Some("withdraw"),
+                None => None,
+            })
+            .collect();
+
+        format!(", Instructions: {}", instruction_types.join(", "))
+    } else {
+        "".to_string()
+    };
+
+    log::info!(
+        "Jetstream Parsed - Transaction received - Signature: {}{}",
+        signature,
+        instruction_summary
+    );
+}
@@ -0,0 +1,262 @@
+use jetstream_client::decoder::registry::DecoderRegistry;
+use jetstream_client::record::RECORD_EXTENSION;
+use jetstream_client::sink::RotatingFileSink;
+use jetstream_client::{RawUpdate, UpdateKind};
+use jetstream_protos::jetstream::SubscribeUpdateParsedTransaction;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::jsonl;
+use crate::output::{self, DecodedUpdate};
+
+/// Encoding of the records written by [`OutputWriter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// One JSON object per transaction
+    Jsonl,
+    /// Length-delimited `SubscribeUpdate` or `SubscribeUpdateParsedTransaction` messages
+    Protobuf,
+    /// Protobuf messages after a [`RecordHeader`](jetstream_client::record::RecordHeader)
+    Record,
+}
+
+impl RecordFormat {
+    /// File extension of segments in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Protobuf => "pb",
+            Self::Record => RECORD_EXTENSION,
+        }
+    }
+}
+
+enum Destination {
+    Stream(Box<dyn Write + Send>),
+    Segments(Box<RotatingFileSink>),
+}
+
+/// Writes updates as records to stdout, a file or rotating segment files
+pub struct OutputWriter {
+    format: RecordFormat,
+    destination: Destination,
+    record: Vec<u8>,
+}
+
+impl OutputWriter {
+    /// Write `header` then the records to the file at `path`, or to stdout if
+    /// there is none. Captures need a file of their own; other formats append.
+    pub fn stream(
+        format: RecordFormat,
+        path: Option<&Path>,
+        header: &[u8],
+    ) -> anyhow::Result<Self> {
+        let mut out: Box<dyn Write + Send> = match path {
+            Some(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(format != RecordFormat::Record)
+                    .create_new(format == RecordFormat::Record)
+                    .write(true)
+                    .open(path)
+                    .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))?;
+                Box::new(BufWriter::new(file))
+            }
+            None => Box::new(std::io::stdout()),
+        };
+        out.write_all(header)?;
+        Ok(Self::new(format, Destination::Stream(out)))
+    }
+
+    /// Write to the segments of `sink`, each starting with `header`
+    pub fn segments(format: RecordFormat, sink: RotatingFileSink, header: Vec<u8>) -> Self {
+        Self::new(
+            format,
+            Destination::Segments(Box::new(sink.with_header(header))),
+        )
+    }
+
+    fn new(format: RecordFormat, destination: Destination) -> Self {
+        Self {
+            format,
+            destination,
+            record: Vec::new(),
+        }
+    }
+
+    /// Whether this format records the raw updates passed to [`Self::write_raw`]
+    /// instead of decoded ones
+    pub fn captures_raw(&self) -> bool {
+        self.format != RecordFormat::Jsonl
+    }
+
+    /// Write a `SubscribeUpdate` or parsed transaction exactly as it was received,
+    /// length-delimited
+    pub fn write_raw(&mut self, raw: &RawUpdate) -> anyhow::Result<()> {
+        self.record.clear();
+        prost::encoding::encode_varint(raw.bytes.len() as u64, &mut self.record);
+        self.record.extend_from_slice(&raw.bytes);
+        self.write_record(raw.slot)
+    }
+
+    /// Write an update as JSON Lines, where account updates are logged as usual.
+    /// Protobuf and record captures take raw updates through [`Self::write_raw`].
+    pub fn write_update(
+        &mut self,
+        decoded: &DecodedUpdate,
+        decoders: &DecoderRegistry,
+    ) -> anyhow::Result<()> {
+        self.record.clear();
+        match (self.format, &decoded.update.kind) {
+            (RecordFormat::Jsonl, UpdateKind::Transaction(tx_info)) => {
+                let value = jsonl::transaction_json(tx_info, decoded, decoders);
+                serde_json::to_writer(&mut self.record, &value)?;
+                self.record.push(b'\n');
+            }
+            (RecordFormat::Jsonl, UpdateKind::Account(_)) => {
+                output::log_update(decoded);
+                return Ok(());
+            }
+            (RecordFormat::Protobuf | RecordFormat::Record, _) => {
+                anyhow::bail!("{:?} output records raw updates", self.format);
+            }
+        }
+        self.write_record(Some(decoded.update.slot()))
+    }
+
+    /// Write a parsed transaction as JSON Lines.
+    /// Protobuf and record captures take raw transactions through [`Self::write_raw`].
+    pub fn write_parsed_transaction(
+        &mut self,
+        parsed_tx: &SubscribeUpdateParsedTransaction,
+    ) -> anyhow::Result<()> {
+        self.record.clear();
+        match self.format {
+            RecordFormat::Jsonl => {
+                if parsed_tx.signature.is_empty() {
+                    return Ok(());
+                }
+                let value = jsonl::parsed_transaction_json(parsed_tx);
+                serde_json::to_writer(&mut self.record, &value)?;
+                self.record.push(b'\n');
+            }
+            RecordFormat::Protobuf | RecordFormat::Record => {
+                anyhow::bail!("{:?} output records raw transactions", self.format);
+            }
+        }
+        self.write_record(Some(parsed_tx.slot))
+    }
+
+    /// Flush buffered records and close the open segment, if any
+    pub fn close(&mut self) -> anyhow::Result<()> {
+        match &mut self.destination {
+            Destination::Stream(out) => Ok(out.flush()?),
+            Destination::Segments(sink) => sink.close(),
+        }
+    }
+
+    fn write_record(&mut self, slot: Option<u64>) -> anyhow::Result<()> {
+        match &mut self.destination {
+            Destination::Stream(out) => Ok(out.write_all(&self.record)?),
+            Destination::Segments(sink) => sink.write_record(&self.record, slot),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jetstream_client::record::{RecordHeader, RecordReader};
+    use jetstream_protos::jetstream::{
+        subscribe_update::UpdateOneof, SubscribeUpdate, SubscribeUpdateAccount,
+        SubscribeUpdateAccountInfo, SubscribeUpdatePing, SubscribeUpdateTransaction,
+        SubscribeUpdateTransactionInfo,
+    };
+    use prost::Message;
+
+    fn raw(update: &SubscribeUpdate, slot: Option<u64>) -> RawUpdate {
+        RawUpdate {
+            endpoint: 0,
+            bytes: update.encode_to_vec().into(),
+            slot,
+        }
+    }
+
+    #[test]
+    fn raw_updates_round_trip_through_record_reader() {
+        let transaction = SubscribeUpdate {
+            filters: vec!["pump".to_string()],
+            created_at: None,
+            update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+                transaction: Some(SubscribeUpdateTransactionInfo {
+                    signature: vec![7; 64],
+                    slot: 42,
+                    ..Default::default()
+                }),
+                slot: 42,
+            })),
+        };
+        let ping = SubscribeUpdate {
+            filters: Vec::new(),
+            created_at: None,
+            update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+        };
+        let account = SubscribeUpdate {
+            filters: vec!["accounts".to_string()],
+            created_at: None,
+            update_oneof: Some(UpdateOneof::Account(SubscribeUpdateAccount {
+                account: Some(SubscribeUpdateAccountInfo {
+                    pubkey: vec![3; 32],
+                    lamports: 1_000,
+                    write_version: 9,
+                    ..Default::default()
+                }),
+                slot: 43,
+                ..Default::default()
+            })),
+        };
+        let updates = [transaction, ping, account];
+
+        let header = RecordHeader::new(vec!["https://a.example".to_string()], None, false);
+        let prefix = header.to_prefix();
+        let dir = std::env::temp_dir().join(format!("writer-round-trip-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("capture.{}", RECORD_EXTENSION));
+        let _ = std::fs::remove_file(&path);
+
+        let mut writer = OutputWriter::stream(RecordFormat::Record, Some(&path), &prefix).unwrap();
+        assert!(writer.captures_raw());
+        let raws = [
+            raw(&updates[0], Some(42)),
+            raw(&updates[1], None),
+            raw(&updates[2], Some(43)),
+        ];
+        for update in &raws {
+            writer.write_raw(update).unwrap();
+        }
+        writer.close().unwrap();
+        drop(writer);
+
+        let written = std::fs::read(&path).unwrap();
+        assert!(written.starts_with(&prefix));
+        let mut expected = Vec::new();
+        for update in &raws {
+            prost::encoding::encode_varint(update.bytes.len() as u64, &mut expected);
+            expected.extend_from_slice(&update.bytes);
+        }
+        assert_eq!(&written[prefix.len()..], expected.as_slice());
+
+        let mut reader = RecordReader::open(&path).unwrap();
+        assert_eq!(reader.header(), Some(&header));
+        for update in &updates {
+            assert_eq!(
+                reader.next_message::<SubscribeUpdate>().unwrap().as_ref(),
+                Some(update)
+            );
+        }
+        assert!(reader.next_message::<SubscribeUpdate>().unwrap().is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
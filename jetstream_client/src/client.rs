@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use jetstream_protos::jetstream::{
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterTransactions,
+};
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+
+use crate::config::{AuthConfig, BackpressureConfig, KeepaliveConfig, ReconnectConfig, TlsConfig};
+use crate::connector::auth::{Auth, TokenSource};
+use crate::connector::connector::jetstream_connector;
+use crate::connector::parsed::jetstream_parsed_connector;
+use crate::connector::stream::{ParsedUpdateStream, UpdateStream};
+use crate::connector::subscription::SubscriptionHandle;
+use crate::update::RawUpdate;
+
+/// Connection settings shared by every session of a client
+#[derive(Debug, Clone)]
+pub(crate) struct ClientSettings {
+    pub endpoints: Vec<String>,
+    pub auth: AuthConfig,
+    pub tls: TlsConfig,
+    pub reconnect: ReconnectConfig,
+    pub keepalive: KeepaliveConfig,
+    pub backpressure: BackpressureConfig,
+    pub connect_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub dedup_capacity: usize,
+    pub race_stats_interval: Duration,
+    pub channel_capacity: usize,
+}
+
+/// Builder for [`JetstreamClient`]
+#[derive(Debug, Clone)]
+pub struct JetstreamClientBuilder {
+    settings: ClientSettings,
+    transactions: HashMap<String, SubscribeRequestFilterTransactions>,
+    accounts: HashMap<String, SubscribeRequestFilterAccounts>,
+}
+
+impl Default for JetstreamClientBuilder {
+    fn default() -> Self {
+        Self {
+            settings: ClientSettings {
+                endpoints: Vec::new(),
+                auth: AuthConfig::default(),
+                tls: TlsConfig::default(),
+                reconnect: ReconnectConfig::default(),
+                keepalive: KeepaliveConfig::default(),
+                backpressure: BackpressureConfig::default(),
+                connect_timeout: None,
+                timeout: None,
+                dedup_capacity: 100_000,
+                race_stats_interval: Duration::from_secs(60),
+                channel_capacity: 10_000,
+            },
+            transactions: HashMap::new(),
+            accounts: HashMap::new(),
+        }
+    }
+}
+
+impl JetstreamClientBuilder {
+    /// Add an endpoint; with several endpoints every update is raced between them
+    pub fn endpoint(mut self, url: impl Into<String>) -> Self {
+        self.settings.endpoints.push(url.into());
+        self
+    }
+
+    /// Replace all endpoints
+    pub fn endpoints<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.settings.endpoints = urls.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Authenticate with a static token using the default header
+    pub fn x_token(mut self, token: impl Into<String>) -> Self {
+        self.settings.auth.token = TokenSource::Static(token.into());
+        self
+    }
+
+    pub fn auth(mut self, auth: AuthConfig) -> Self {
+        self.settings.auth = auth;
+        self
+    }
+
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.settings.tls = tls;
+        self
+    }
+
+    pub fn reconnect(mut self, reconnect: ReconnectConfig) -> Self {
+        self.settings.reconnect = reconnect;
+        self
+    }
+
+    pub fn keepalive(mut self, keepalive: KeepaliveConfig) -> Self {
+        self.settings.keepalive = keepalive;
+        self
+    }
+
+    /// What to do with updates when the consumer falls behind
+    pub fn backpressure(mut self, backpressure: BackpressureConfig) -> Self {
+        self.settings.backpressure = backpressure;
+        self
+    }
+
+    /// Timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.settings.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for a request to receive its response headers
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.settings.timeout = Some(timeout);
+        self
+    }
+
+    /// Number of recent updates remembered to deduplicate raced endpoints
+    pub fn dedup_capacity(mut self, capacity: usize) -> Self {
+        self.settings.dedup_capacity = capacity;
+        self
+    }
+
+    /// Interval between per-endpoint race statistics logs (at least one second)
+    pub fn race_stats_interval(mut self, interval: Duration) -> Self {
+        self.settings.race_stats_interval = interval.max(Duration::from_secs(1));
+        self
+    }
+
+    /// Number of updates buffered before the backpressure policy applies
+    pub fn channel_capacity(mut self, capacity: usize) -> Self {
+        self.settings.channel_capacity = capacity.max(1);
+        self
+    }
+
+    /// Add a named transaction filter
+    pub fn transaction_filter(
+        mut self,
+        name: impl Into<String>,
+        filter: SubscribeRequestFilterTransactions,
+    ) -> Self {
+        self.transactions.insert(name.into(), filter);
+        self
+    }
+
+    /// Replace all transaction filters
+    pub fn transaction_filters(
+        mut self,
+        filters: HashMap<String, SubscribeRequestFilterTransactions>,
+    ) -> Self {
+        self.transactions = filters;
+        self
+    }
+
+    /// Add a named account filter
+    pub fn account_filter(
+        mut self,
+        name: impl Into<String>,
+        filter: SubscribeRequestFilterAccounts,
+    ) -> Self {
+        self.accounts.insert(name.into(), filter);
+        self
+    }
+
+    /// Replace all account filters
+    pub fn account_filters(
+        mut self,
+        filters: HashMap<String, SubscribeRequestFilterAccounts>,
+    ) -> Self {
+        self.accounts = filters;
+        self
+    }
+
+    /// Validate the settings and read the initial auth token
+    pub fn build(self) -> anyhow::Result<JetstreamClient> {
+        if self.settings.endpoints.is_empty() {
+            return Err(anyhow::anyhow!("No Jetstream gRPC URL configured"));
+        }
+        if self.settings.tls.client_cert.is_some() != self.settings.tls.client_key.is_some() {
+            return Err(anyhow::anyhow!(
+                "TLS client certificate and key must be set together"
+            ));
+        }
+
+        let auth = Auth::new(&self.settings.auth)?;
+        Ok(JetstreamClient {
+            settings: Arc::new(self.settings),
+            auth,
+            subscription: SubscriptionHandle::new(self.transactions, self.accounts),
+        })
+    }
+}
+
+/// Jetstream client; cheap to clone, clones share the subscription filters
+#[derive(Debug, Clone)]
+pub struct JetstreamClient {
+    settings: Arc<ClientSettings>,
+    auth: Auth,
+    subscription: SubscriptionHandle,
+}
+
+impl JetstreamClient {
+    pub fn builder() -> JetstreamClientBuilder {
+        JetstreamClientBuilder::default()
+    }
+
+    pub fn endpoints(&self) -> &[String] {
+        &self.settings.endpoints
+    }
+
+    /// Handle for changing the filters of open and future subscriptions
+    pub fn subscription(&self) -> &SubscriptionHandle {
+        &self.subscription
+    }
+
+    /// Subscribe to transaction and account updates, racing all endpoints.
+    ///
+    /// Sessions reconnect with backoff; dropping the stream closes them.
+    pub fn subscribe(&self) -> UpdateStream {
+        jetstream_connector(
+            self.settings.clone(),
+            self.auth.clone(),
+            self.subscription.clone(),
+            None,
+        )
+    }
+
+    /// Like [`JetstreamClient::subscribe`], also returning every message received
+    /// from any endpoint, before deduplication and with pings and pongs, as the
+    /// bytes it arrived as. Up to `capacity` messages are buffered; a full tap
+    /// holds back the endpoint that fills it, a dropped tap is ignored.
+    pub fn subscribe_with_tap(&self, capacity: usize) -> (UpdateStream, mpsc::Receiver<RawUpdate>) {
+        let (tap, raw) = mpsc::channel(capacity.max(1));
+        let stream = jetstream_connector(
+            self.settings.clone(),
+            self.auth.clone(),
+            self.subscription.clone(),
+            Some(tap),
+        );
+        (stream, raw)
+    }
+
+    /// Subscribe to parsed pump.fun transactions from the first endpoint
+    pub fn subscribe_parsed(&self) -> ParsedUpdateStream {
+        jetstream_parsed_connector(self.settings.clone(), self.auth.clone(), None)
+    }
+
+    /// Like [`JetstreamClient::subscribe_parsed`], also returning every parsed
+    /// transaction as the bytes it arrived as, buffering up to `capacity`
+    pub fn subscribe_parsed_with_tap(
+        &self,
+        capacity: usize,
+    ) -> (ParsedUpdateStream, mpsc::Receiver<RawUpdate>) {
+        let (tap, raw) = mpsc::channel(capacity.max(1));
+        let stream =
+            jetstream_parsed_connector(self.settings.clone(), self.auth.clone(), Some(tap));
+        (stream, raw)
+    }
+}
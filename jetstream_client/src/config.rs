@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use jetstream_protos::jetstream::{
+    subscribe_request_filter_accounts_filter::Filter as AccountsFilter,
+    subscribe_request_filter_accounts_filter_lamports::Cmp as LamportsCmp,
+    subscribe_request_filter_accounts_filter_memcmp::Data as MemcmpData,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterAccountsFilter,
+    SubscribeRequestFilterAccountsFilterLamports, SubscribeRequestFilterAccountsFilterMemcmp,
+    SubscribeRequestFilterTransactions,
+};
+use solana_sdk::pubkey::Pubkey;
+use tokio::time::Duration;
+
+use crate::connector::auth::TokenSource;
+use crate::decoder::anchor_idl::AnchorIdlDecoder;
+use crate::decoder::registry::DecoderRegistry;
+
+/// Represents a transaction and account filter configuration
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct FilterConfig {
+    #[serde(default)]
+    pub filters: HashMap<String, Filter>,
+    #[serde(default)]
+    pub accounts: HashMap<String, AccountFilter>,
+    /// Anchor IDL files keyed by the program id they decode
+    #[serde(default)]
+    pub idls: HashMap<String, PathBuf>,
+}
+
+impl FilterConfig {
+    /// Load a filter configuration from a JSON file
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        log::info!("Loading filters from file: {}", path.display());
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let mut config: Self = serde_json::from_reader(reader)?;
+
+        // IDL paths are relative to the filter file
+        if let Some(dir) = path.parent() {
+            for idl in config.idls.values_mut() {
+                if idl.is_relative() {
+                    *idl = dir.join(&*idl);
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Load the configured IDLs and register a decoder for each program
+    pub fn register_idls(&self, registry: &mut DecoderRegistry) -> anyhow::Result<()> {
+        for (program_id, path) in &self.idls {
+            let program_id = Pubkey::from_str(program_id)
+                .map_err(|e| anyhow::anyhow!("Invalid IDL program id {}: {}", program_id, e))?;
+            let decoder = AnchorIdlDecoder::from_file(path, Some(program_id))?;
+            log::info!(
+                "Decoding {} instructions with IDL {}",
+                program_id,
+                path.display()
+            );
+            registry.register(Arc::new(decoder));
+        }
+        Ok(())
+    }
+
+    /// Transaction filters in protobuf form
+    pub fn transaction_filters(&self) -> HashMap<String, SubscribeRequestFilterTransactions> {
+        self.filters
+            .iter()
+            .map(|(name, filter)| (name.clone(), filter.into()))
+            .collect()
+    }
+
+    /// Account filters in protobuf form
+    pub fn account_filters(
+        &self,
+    ) -> anyhow::Result<HashMap<String, SubscribeRequestFilterAccounts>> {
+        self.accounts
+            .iter()
+            .map(|(name, filter)| Ok((name.clone(), filter.try_into()?)))
+            .collect()
+    }
+}
+
+/// Individual filter settings
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Filter {
+    #[serde(default)]
+    pub account_include: Vec<String>,
+    #[serde(default)]
+    pub account_exclude: Vec<String>,
+    #[serde(default)]
+    pub account_required: Vec<String>,
+}
+
+impl From<&Filter> for SubscribeRequestFilterTransactions {
+    fn from(filter: &Filter) -> Self {
+        Self {
+            account_include: filter.account_include.clone(),
+            account_exclude: filter.account_exclude.clone(),
+            account_required: filter.account_required.clone(),
+        }
+    }
+}
+
+/// Individual account subscription settings
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AccountFilter {
+    #[serde(default)]
+    pub account: Vec<String>,
+    #[serde(default)]
+    pub owner: Vec<String>,
+    #[serde(default)]
+    pub filters: Vec<AccountDataFilter>,
+}
+
+impl TryFrom<&AccountFilter> for SubscribeRequestFilterAccounts {
+    type Error = anyhow::Error;
+
+    fn try_from(filter: &AccountFilter) -> anyhow::Result<Self> {
+        let filters = filter
+            .filters
+            .iter()
+            .map(|data_filter| {
+                let filter = match data_filter {
+                    AccountDataFilter::Memcmp(memcmp) => {
+                        let data = match (&memcmp.bytes, &memcmp.base58, &memcmp.base64) {
+                            (Some(bytes), None, None) => MemcmpData::Bytes(bytes.clone()),
+                            (None, Some(base58), None) => MemcmpData::Base58(base58.clone()),
+                            (None, None, Some(base64)) => MemcmpData::Base64(base64.clone()),
+                            _ => {
+                                return Err(anyhow::anyhow!(
+                                "memcmp filter at offset {} needs exactly one of bytes, base58 or base64",
+                                memcmp.offset
+                            ))
+                            }
+                        };
+                        AccountsFilter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                            offset: memcmp.offset,
+                            data: Some(data),
+                        })
+                    }
+                    AccountDataFilter::Datasize(datasize) => AccountsFilter::Datasize(*datasize),
+                    AccountDataFilter::Lamports(lamports) => {
+                        let cmp = match lamports {
+                            LamportsFilter::Eq(value) => LamportsCmp::Eq(*value),
+                            LamportsFilter::Ne(value) => LamportsCmp::Ne(*value),
+                            LamportsFilter::Lt(value) => LamportsCmp::Lt(*value),
+                            LamportsFilter::Gt(value) => LamportsCmp::Gt(*value),
+                        };
+                        AccountsFilter::Lamports(SubscribeRequestFilterAccountsFilterLamports {
+                            cmp: Some(cmp),
+                        })
+                    }
+                };
+                Ok(SubscribeRequestFilterAccountsFilter {
+                    filter: Some(filter),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            account: filter.account.clone(),
+            owner: filter.owner.clone(),
+            filters,
+        })
+    }
+}
+
+/// Account data filter, e.g. `{"datasize": 165}` or `{"lamports": {"gt": 0}}`
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountDataFilter {
+    Memcmp(MemcmpFilter),
+    Datasize(u64),
+    Lamports(LamportsFilter),
+}
+
+/// Compare account data at `offset`; exactly one of the data fields must be set
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MemcmpFilter {
+    pub offset: u64,
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
+    #[serde(default)]
+    pub base58: Option<String>,
+    #[serde(default)]
+    pub base64: Option<String>,
+}
+
+impl FromStr for MemcmpFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (offset, data) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected OFFSET:BASE58, got '{}'", s))?;
+        Ok(Self {
+            offset: offset
+                .parse()
+                .map_err(|e| format!("invalid memcmp offset '{}': {}", offset, e))?,
+            bytes: None,
+            base58: Some(data.to_string()),
+            base64: None,
+        })
+    }
+}
+
+/// Lamports comparison for account filters
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LamportsFilter {
+    Eq(u64),
+    Ne(u64),
+    Lt(u64),
+    Gt(u64),
+}
+
+impl FromStr for LamportsFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (op, value) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected OP:VALUE, got '{}'", s))?;
+        let value = value
+            .parse()
+            .map_err(|e| format!("invalid lamports value '{}': {}", value, e))?;
+        match op {
+            "eq" => Ok(Self::Eq(value)),
+            "ne" => Ok(Self::Ne(value)),
+            "lt" => Ok(Self::Lt(value)),
+            "gt" => Ok(Self::Gt(value)),
+            _ => Err(format!("unknown lamports operator '{}'", op)),
+        }
+    }
+}
+
+/// Token authentication settings
+#[derive(Debug, Clone)]
+pub struct AuthConfig {
+    pub token: TokenSource,
+    /// Metadata header carrying the token
+    pub header: String,
+    /// Prefix the token with "Bearer "
+    pub bearer: bool,
+    /// Interval for re-reading file or env tokens (zero disables refresh)
+    pub refresh_interval: Duration,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            token: TokenSource::None,
+            header: "authorization".to_string(),
+            bearer: false,
+            refresh_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// TLS settings for `https://` endpoints
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// PEM bundle of additional trusted CA certificates
+    pub ca_cert: Option<PathBuf>,
+    /// Do not trust the system root certificates
+    pub no_system_roots: bool,
+    /// PEM client certificate for mutual TLS
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1) for the client certificate
+    pub client_key: Option<PathBuf>,
+    /// Override the server name used for SNI and certificate verification
+    pub domain_name: Option<String>,
+}
+
+impl TlsConfig {
+    pub(crate) fn is_customized(&self) -> bool {
+        self.ca_cert.is_some() || self.client_cert.is_some() || self.domain_name.is_some()
+    }
+}
+
+/// Reconnect backoff settings
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// Maximum consecutive reconnect attempts (0 disables reconnects, unlimited if unset)
+    pub max_attempts: Option<u32>,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// Random jitter applied to the delay (fraction, 0.0 - 1.0)
+    pub jitter: f64,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: None,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+/// Ping settings for the subscribe stream
+#[derive(Debug, Clone)]
+pub struct KeepaliveConfig {
+    /// Interval between client pings (zero disables pings)
+    pub interval: Duration,
+    /// Consecutive unanswered pings before the connection is considered dead
+    pub max_missed: u32,
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(15),
+            max_missed: 3,
+        }
+    }
+}
+
+/// What to do with updates when the consumer falls behind
+#[derive(Debug, Clone, Default)]
+pub enum BackpressurePolicy {
+    /// Stop reading from the server until the consumer catches up
+    Block,
+    /// Discard incoming updates while the buffer is full
+    #[default]
+    DropNewest,
+    /// Discard the oldest buffered update to make room
+    DropOldest,
+    /// Write overflow to a temporary file in `dir` and replay it in order
+    SpillToDisk { dir: PathBuf },
+}
+
+impl fmt::Display for BackpressurePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Block => write!(f, "block"),
+            Self::DropNewest => write!(f, "drop-newest"),
+            Self::DropOldest => write!(f, "drop-oldest"),
+            Self::SpillToDisk { .. } => write!(f, "spill-to-disk"),
+        }
+    }
+}
+
+/// Buffering settings between the network and the consumer
+#[derive(Debug, Clone)]
+pub struct BackpressureConfig {
+    pub policy: BackpressurePolicy,
+    /// Minimum interval between warnings about dropped updates
+    pub warn_interval: Duration,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            policy: BackpressurePolicy::default(),
+            warn_interval: Duration::from_secs(10),
+        }
+    }
+}
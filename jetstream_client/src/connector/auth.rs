@@ -0,0 +1,251 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+use tonic::metadata::{Ascii, MetadataKey, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+use crate::config::AuthConfig;
+
+/// Where the authentication token is read from
+#[derive(Debug, Clone)]
+pub enum TokenSource {
+    None,
+    Static(String),
+    File(PathBuf),
+    Env(String),
+}
+
+impl TokenSource {
+    fn read(&self) -> anyhow::Result<Option<String>> {
+        let token = match self {
+            Self::None => return Ok(None),
+            Self::Static(token) => token.clone(),
+            Self::File(path) => fs::read_to_string(path).map_err(|e| {
+                anyhow::anyhow!("Failed to read token file {}: {}", path.display(), e)
+            })?,
+            Self::Env(var) => std::env::var(var)
+                .map_err(|e| anyhow::anyhow!("Failed to read token from ${}: {}", var, e))?,
+        };
+
+        let token = token.trim();
+        Ok((!token.is_empty()).then(|| token.to_string()))
+    }
+
+    fn is_refreshable(&self) -> bool {
+        matches!(self, Self::File(_) | Self::Env(_))
+    }
+}
+
+/// Authentication layer shared by the raw and parsed connectors.
+///
+/// Cloning is cheap; clones share the current token, so a refresh is picked up
+/// by every client built from the same `Auth`.
+#[derive(Debug, Clone)]
+pub struct Auth {
+    header: MetadataKey<Ascii>,
+    bearer: bool,
+    source: TokenSource,
+    token: Arc<RwLock<Option<MetadataValue<Ascii>>>>,
+}
+
+impl Auth {
+    pub fn new(config: &AuthConfig) -> anyhow::Result<Self> {
+        let header = MetadataKey::from_bytes(config.header.to_lowercase().as_bytes())
+            .map_err(|e| anyhow::anyhow!("Invalid auth header name '{}': {}", config.header, e))?;
+
+        let auth = Self {
+            header,
+            bearer: config.bearer,
+            source: config.token.clone(),
+            token: Arc::new(RwLock::new(None)),
+        };
+        auth.refresh()?;
+        Ok(auth)
+    }
+
+    /// Re-read the token from its source. Returns true if the token changed.
+    pub fn refresh(&self) -> anyhow::Result<bool> {
+        let value = match self.source.read()? {
+            Some(token) => {
+                let token = if self.bearer {
+                    format!("Bearer {}", token)
+                } else {
+                    token
+                };
+                Some(
+                    token
+                        .parse::<MetadataValue<Ascii>>()
+                        .map_err(|e| anyhow::anyhow!("Failed to parse auth token: {}", e))?,
+                )
+            }
+            None => None,
+        };
+
+        let mut current = self.token.write().expect("auth token lock poisoned");
+        let changed = *current != value;
+        *current = value;
+        Ok(changed)
+    }
+
+    /// Periodically re-read file or env tokens so new requests use the latest value
+    pub fn spawn_refresh(&self, interval: Duration) -> Option<JoinHandle<()>> {
+        if interval.is_zero() || !self.source.is_refreshable() {
+            return None;
+        }
+
+        let auth = self.clone();
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match auth.refresh() {
+                    Ok(true) => log::info!("Auth token refreshed"),
+                    Ok(false) => {}
+                    Err(e) => log::warn!("Failed to refresh auth token: {:#}", e),
+                }
+            }
+        }))
+    }
+}
+
+impl Interceptor for Auth {
+    fn call(&mut self, mut req: Request<()>) -> Result<Request<()>, Status> {
+        let token = self.token.read().expect("auth token lock poisoned");
+        if let Some(value) = token.as_ref() {
+            req.metadata_mut()
+                .insert(self.header.clone(), value.clone());
+        }
+        Ok(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(token: TokenSource, header: &str, bearer: bool) -> anyhow::Result<Auth> {
+        Auth::new(&AuthConfig {
+            token,
+            header: header.to_string(),
+            bearer,
+            ..Default::default()
+        })
+    }
+
+    /// Value the interceptor puts in `header`
+    fn sent(auth: &Auth, header: &str) -> Option<String> {
+        let req = auth.clone().call(Request::new(())).unwrap();
+        req.metadata()
+            .get(header)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("auth-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn token_goes_in_the_configured_header() {
+        let auth = auth(TokenSource::Static("secret".into()), "X-Token", false).unwrap();
+        assert_eq!(sent(&auth, "x-token").as_deref(), Some("secret"));
+        assert_eq!(sent(&auth, "authorization"), None);
+    }
+
+    #[test]
+    fn bearer_prefixes_the_token() {
+        let auth = auth(TokenSource::Static("secret".into()), "authorization", true).unwrap();
+        assert_eq!(
+            sent(&auth, "authorization").as_deref(),
+            Some("Bearer secret")
+        );
+    }
+
+    #[test]
+    fn no_or_blank_token_sends_no_header() {
+        for source in [TokenSource::None, TokenSource::Static("  \n".into())] {
+            let auth = auth(source, "authorization", true).unwrap();
+            assert_eq!(sent(&auth, "authorization"), None);
+        }
+    }
+
+    #[test]
+    fn file_and_env_tokens_are_read_and_trimmed() {
+        let path = temp_path("file");
+        fs::write(&path, "from-file\n").unwrap();
+        let auth_file = auth(TokenSource::File(path.clone()), "authorization", false).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            sent(&auth_file, "authorization").as_deref(),
+            Some("from-file")
+        );
+
+        let var = format!("JETSTREAM_AUTH_TEST_{}", std::process::id());
+        std::env::set_var(&var, " from-env ");
+        let auth_env = auth(TokenSource::Env(var.clone()), "authorization", false).unwrap();
+        std::env::remove_var(&var);
+        assert_eq!(
+            sent(&auth_env, "authorization").as_deref(),
+            Some("from-env")
+        );
+    }
+
+    #[test]
+    fn missing_sources_and_invalid_values_are_rejected() {
+        let missing = TokenSource::File(temp_path("missing"));
+        assert!(auth(missing, "authorization", false).is_err());
+        let unset = TokenSource::Env("JETSTREAM_AUTH_TEST_UNSET".into());
+        assert!(auth(unset, "authorization", false).is_err());
+        let invalid = TokenSource::Static("line\nbreak".into());
+        assert!(auth(invalid, "authorization", false).is_err());
+        assert!(auth(TokenSource::None, "bad header", false).is_err());
+    }
+
+    #[test]
+    fn refresh_reports_changes() {
+        let path = temp_path("refresh");
+        fs::write(&path, "one").unwrap();
+        let auth = auth(TokenSource::File(path.clone()), "authorization", false).unwrap();
+        assert!(!auth.refresh().unwrap());
+        fs::write(&path, "two").unwrap();
+        assert!(auth.refresh().unwrap());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(sent(&auth, "authorization").as_deref(), Some("two"));
+    }
+
+    #[tokio::test]
+    async fn spawn_refresh_picks_up_new_file_tokens() {
+        let path = temp_path("spawn");
+        fs::write(&path, "old").unwrap();
+        let auth = auth(TokenSource::File(path.clone()), "authorization", false).unwrap();
+        let task = auth.spawn_refresh(Duration::from_millis(20)).unwrap();
+
+        fs::write(&path, "new").unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while sent(&auth, "authorization").as_deref() != Some("new") {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "token not refreshed"
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        task.abort();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn spawn_refresh_skips_static_tokens_and_zero_interval() {
+        let fixed = auth(TokenSource::Static("t".into()), "authorization", false).unwrap();
+        assert!(fixed.spawn_refresh(Duration::from_secs(1)).is_none());
+
+        let path = temp_path("zero");
+        fs::write(&path, "t").unwrap();
+        let file = auth(TokenSource::File(path.clone()), "authorization", false).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(file.spawn_refresh(Duration::ZERO).is_none());
+    }
+}
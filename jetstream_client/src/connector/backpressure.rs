@@ -0,0 +1,579 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use jetstream_protos::jetstream::{SubscribeUpdate, SubscribeUpdateParsedTransaction};
+use prost::Message;
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant};
+
+use crate::config::{BackpressureConfig, BackpressurePolicy};
+
+static SPILL_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Items that can be written to a spill file
+pub(crate) trait Spill: Sized + Send + 'static {
+    fn encode_spill(&self, buf: &mut Vec<u8>);
+    fn decode_spill(buf: &[u8]) -> anyhow::Result<Self>;
+}
+
+impl Spill for (usize, SubscribeUpdate) {
+    fn encode_spill(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&(self.0 as u32).to_le_bytes());
+        self.1.encode(buf).expect("Vec has unlimited capacity");
+    }
+
+    fn decode_spill(buf: &[u8]) -> anyhow::Result<Self> {
+        let (index, msg) = buf
+            .split_first_chunk::<4>()
+            .ok_or_else(|| anyhow::anyhow!("Spilled update is truncated"))?;
+        Ok((
+            u32::from_le_bytes(*index) as usize,
+            SubscribeUpdate::decode(msg)?,
+        ))
+    }
+}
+
+impl Spill for SubscribeUpdateParsedTransaction {
+    fn encode_spill(&self, buf: &mut Vec<u8>) {
+        self.encode(buf).expect("Vec has unlimited capacity");
+    }
+
+    fn decode_spill(buf: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self::decode(buf)?)
+    }
+}
+
+/// Length-prefixed overflow file, drained in the order it was written
+struct SpillFile {
+    writer: BufWriter<File>,
+    reader: BufReader<File>,
+    pending: u64,
+}
+
+impl SpillFile {
+    fn create(dir: &Path, label: &str) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "jetstream-{}-{}-{}.spill",
+            label,
+            std::process::id(),
+            SPILL_FILE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&path)
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to create spill file {}: {}", path.display(), e)
+            })?;
+        // A separate handle keeps its own read position
+        let reader = File::open(&path)?;
+        // The file stays usable through the open handles and disappears on exit
+        let _ = std::fs::remove_file(&path);
+        log::info!("Spilling {} overflow to {}", label, path.display());
+
+        Ok(Self {
+            reader: BufReader::new(reader),
+            writer: BufWriter::new(file),
+            pending: 0,
+        })
+    }
+
+    fn push(&mut self, item: &[u8]) -> std::io::Result<()> {
+        self.writer.write_all(&(item.len() as u32).to_le_bytes())?;
+        self.writer.write_all(item)?;
+        self.pending += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> std::io::Result<Vec<u8>> {
+        self.writer.flush()?;
+        let mut len = [0; 4];
+        self.reader.read_exact(&mut len)?;
+        let mut item = vec![0; u32::from_le_bytes(len) as usize];
+        self.reader.read_exact(&mut item)?;
+        self.pending -= 1;
+
+        if self.pending == 0 {
+            // Fully drained, reclaim the disk space
+            let file = self.writer.get_mut();
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+            self.reader.seek(SeekFrom::Start(0))?;
+        }
+        Ok(item)
+    }
+}
+
+struct State<T> {
+    items: VecDeque<T>,
+    /// Items sent to the spill file and not yet received, including writes in flight
+    spilled: u64,
+    receiver_closed: bool,
+    dropped_since_warning: u64,
+    last_warning: Option<Instant>,
+}
+
+/// Outcome of reading the spill file
+enum Unspilled<T> {
+    Item(T),
+    /// The item was lost, try again
+    Skipped,
+    /// The next item is still being written
+    Pending,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    /// Only touched from blocking tasks, never while `state` is locked
+    spill_file: Mutex<Option<SpillFile>>,
+    capacity: usize,
+    policy: BackpressurePolicy,
+    warn_interval: Duration,
+    label: &'static str,
+    dropped: Arc<AtomicU64>,
+    senders: AtomicUsize,
+    not_empty: Notify,
+    not_full: Notify,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State<T>> {
+        self.state.lock().expect("backpressure queue lock poisoned")
+    }
+
+    fn record_drop(&self, state: &mut State<T>) {
+        let total = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        state.dropped_since_warning += 1;
+        if state
+            .last_warning
+            .is_none_or(|last| last.elapsed() >= self.warn_interval)
+        {
+            log::warn!(
+                "Backpressure ({}): dropped {} {} update(s) since the last warning, {} in total",
+                self.policy,
+                state.dropped_since_warning,
+                self.label,
+                total
+            );
+            state.dropped_since_warning = 0;
+            state.last_warning = Some(Instant::now());
+        }
+    }
+
+    fn lock_spill_file(&self) -> std::sync::MutexGuard<'_, Option<SpillFile>> {
+        self.spill_file.lock().expect("spill file lock poisoned")
+    }
+}
+
+impl<T: Spill> Shared<T> {
+    /// Append an item to the spill file; blocking
+    fn spill(&self, dir: &Path, item: T) {
+        let mut buf = Vec::new();
+        item.encode_spill(&mut buf);
+
+        let mut file = self.lock_spill_file();
+        let result = match file.as_mut() {
+            Some(file) => file.push(&buf).map_err(Into::into),
+            None => SpillFile::create(dir, self.label).and_then(|created| {
+                file.insert(created).push(&buf)?;
+                Ok(())
+            }),
+        };
+        drop(file);
+
+        if let Err(e) = result {
+            log::warn!("Failed to spill {} update to disk: {:#}", self.label, e);
+            let mut state = self.lock();
+            state.spilled -= 1;
+            self.record_drop(&mut state);
+        }
+        self.not_empty.notify_one();
+    }
+
+    /// Take the oldest item from the spill file; blocking
+    fn unspill(&self) -> Unspilled<T> {
+        let mut file = self.lock_spill_file();
+        let Some(spill) = file.as_mut().filter(|spill| spill.pending > 0) else {
+            return Unspilled::Pending;
+        };
+
+        match spill.pop() {
+            Ok(buf) => {
+                drop(file);
+                let mut state = self.lock();
+                state.spilled -= 1;
+                match T::decode_spill(&buf) {
+                    Ok(item) => Unspilled::Item(item),
+                    Err(e) => {
+                        log::warn!("Failed to decode spilled {} update: {:#}", self.label, e);
+                        self.record_drop(&mut state);
+                        Unspilled::Skipped
+                    }
+                }
+            }
+            Err(e) => {
+                // The file can no longer be trusted, discard what is left of it
+                let pending = spill.pending;
+                *file = None;
+                drop(file);
+                log::warn!(
+                    "Failed to read {} spill file, discarding {} update(s): {}",
+                    self.label,
+                    pending,
+                    e
+                );
+                let mut state = self.lock();
+                state.spilled -= pending;
+                for _ in 0..pending {
+                    self.record_drop(&mut state);
+                }
+                Unspilled::Skipped
+            }
+        }
+    }
+}
+
+/// Sending half of a bounded queue that applies the configured [`BackpressurePolicy`]
+pub(crate) struct QueueSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Receiving half of a bounded queue that applies the configured [`BackpressurePolicy`]
+pub(crate) struct QueueReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Error returned when the receiver was dropped
+#[derive(Debug)]
+pub(crate) struct Closed;
+
+/// Create a queue holding up to `capacity` items in memory
+pub(crate) fn queue<T: Spill>(
+    capacity: usize,
+    config: &BackpressureConfig,
+    label: &'static str,
+    dropped: Arc<AtomicU64>,
+) -> (QueueSender<T>, QueueReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            items: VecDeque::with_capacity(capacity.min(16_384)),
+            spilled: 0,
+            receiver_closed: false,
+            dropped_since_warning: 0,
+            last_warning: None,
+        }),
+        spill_file: Mutex::new(None),
+        capacity: capacity.max(1),
+        policy: config.policy.clone(),
+        warn_interval: config.warn_interval,
+        label,
+        dropped,
+        senders: AtomicUsize::new(1),
+        not_empty: Notify::new(),
+        not_full: Notify::new(),
+    });
+    (
+        QueueSender {
+            shared: shared.clone(),
+        },
+        QueueReceiver { shared },
+    )
+}
+
+impl<T: Spill> QueueSender<T> {
+    /// Enqueue an item, waiting for room only under [`BackpressurePolicy::Block`]
+    pub async fn send(&self, item: T) -> Result<(), Closed> {
+        let shared = &self.shared;
+        let mut item = Some(item);
+
+        loop {
+            let notified = shared.not_full.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let spill_dir = {
+                let mut state = shared.lock();
+                if state.receiver_closed {
+                    return Err(Closed);
+                }
+
+                if state.items.len() < shared.capacity && state.spilled == 0 {
+                    state.items.extend(item.take());
+                    drop(state);
+                    shared.not_empty.notify_one();
+                    return Ok(());
+                }
+
+                match &shared.policy {
+                    BackpressurePolicy::Block => None,
+                    BackpressurePolicy::DropNewest => {
+                        shared.record_drop(&mut state);
+                        return Ok(());
+                    }
+                    BackpressurePolicy::DropOldest => {
+                        state.items.pop_front();
+                        state.items.extend(item.take());
+                        shared.record_drop(&mut state);
+                        return Ok(());
+                    }
+                    BackpressurePolicy::SpillToDisk { dir } => {
+                        state.spilled += 1;
+                        Some(dir.clone())
+                    }
+                }
+            };
+
+            if let Some(dir) = spill_dir {
+                // File I/O runs off the runtime and outside the queue lock
+                let item = item.take().expect("item is only taken once");
+                let shared = shared.clone();
+                tokio::task::spawn_blocking(move || shared.spill(&dir, item))
+                    .await
+                    .expect("spill task panicked");
+                return Ok(());
+            }
+            notified.await;
+        }
+    }
+}
+
+impl<T> Clone for QueueSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::AcqRel);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for QueueSender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.not_empty.notify_one();
+        }
+    }
+}
+
+impl<T: Spill> QueueReceiver<T> {
+    /// Next item in arrival order, or `None` once every sender is gone and the queue is empty
+    pub async fn recv(&mut self) -> Option<T> {
+        let shared = &self.shared;
+
+        loop {
+            let notified = shared.not_empty.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let spilled = {
+                let mut state = shared.lock();
+                if let Some(item) = state.items.pop_front() {
+                    drop(state);
+                    shared.not_full.notify_one();
+                    return Some(item);
+                }
+
+                if state.spilled == 0 && shared.senders.load(Ordering::Acquire) == 0 {
+                    return None;
+                }
+                state.spilled > 0
+            };
+
+            if spilled {
+                let shared = shared.clone();
+                match tokio::task::spawn_blocking(move || shared.unspill())
+                    .await
+                    .expect("spill task panicked")
+                {
+                    Unspilled::Item(item) => return Some(item),
+                    Unspilled::Skipped => continue,
+                    // The sender notifies once the write lands
+                    Unspilled::Pending => {}
+                }
+            }
+            notified.await;
+        }
+    }
+}
+
+impl<T> Drop for QueueReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_closed = true;
+        self.shared.not_full.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Item = (usize, SubscribeUpdate);
+
+    fn item(n: usize) -> Item {
+        let update = SubscribeUpdate {
+            filters: vec![n.to_string()],
+            ..Default::default()
+        };
+        (n % 3, update)
+    }
+
+    fn id(item: &Item) -> usize {
+        assert_eq!(item.0, item.1.filters[0].parse::<usize>().unwrap() % 3);
+        item.1.filters[0].parse().unwrap()
+    }
+
+    fn test_queue(
+        capacity: usize,
+        policy: BackpressurePolicy,
+    ) -> (QueueSender<Item>, QueueReceiver<Item>, Arc<AtomicU64>) {
+        let dropped = Arc::new(AtomicU64::new(0));
+        let config = BackpressureConfig {
+            policy,
+            warn_interval: Duration::from_secs(10),
+        };
+        let (tx, rx) = queue(capacity, &config, "test", dropped.clone());
+        (tx, rx, dropped)
+    }
+
+    /// Every remaining item after the senders are gone
+    async fn drain(rx: &mut QueueReceiver<Item>) -> Vec<usize> {
+        let mut ids = Vec::new();
+        while let Some(item) = rx.recv().await {
+            ids.push(id(&item));
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn block_waits_for_room_and_keeps_order() {
+        let (tx, mut rx, dropped) = test_queue(2, BackpressurePolicy::Block);
+        tx.send(item(0)).await.unwrap();
+        tx.send(item(1)).await.unwrap();
+        let full = tokio::time::timeout(Duration::from_millis(50), tx.send(item(2))).await;
+        assert!(full.is_err(), "send should wait while the queue is full");
+
+        let producer = tokio::spawn(async move {
+            for n in 2..50 {
+                tx.send(item(n)).await.unwrap();
+            }
+        });
+        assert_eq!(drain(&mut rx).await, (0..50).collect::<Vec<_>>());
+        producer.await.unwrap();
+        assert_eq!(dropped.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn drop_newest_discards_incoming_updates() {
+        let (tx, mut rx, dropped) = test_queue(3, BackpressurePolicy::DropNewest);
+        for n in 0..6 {
+            tx.send(item(n)).await.unwrap();
+        }
+        assert_eq!(dropped.load(Ordering::Relaxed), 3);
+
+        assert_eq!(id(&rx.recv().await.unwrap()), 0);
+        tx.send(item(6)).await.unwrap();
+        drop(tx);
+        assert_eq!(drain(&mut rx).await, [1, 2, 6]);
+        assert_eq!(dropped.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_discards_buffered_updates() {
+        let (tx, mut rx, dropped) = test_queue(3, BackpressurePolicy::DropOldest);
+        for n in 0..6 {
+            tx.send(item(n)).await.unwrap();
+        }
+        drop(tx);
+        assert_eq!(drain(&mut rx).await, [3, 4, 5]);
+        assert_eq!(dropped.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn spill_to_disk_replays_overflow_in_order() {
+        let dir = std::env::temp_dir().join(format!("jetstream-spill-{}", std::process::id()));
+        let policy = BackpressurePolicy::SpillToDisk { dir: dir.clone() };
+        let (tx, mut rx, dropped) = test_queue(2, policy);
+
+        // 0 and 1 fit in memory, 2..6 overflow to the spill file
+        for n in 0..6 {
+            tx.send(item(n)).await.unwrap();
+        }
+        assert_eq!(id(&rx.recv().await.unwrap()), 0);
+        // There is room in memory again, but the spill file must drain first
+        tx.send(item(6)).await.unwrap();
+        for expected in 1..=6 {
+            assert_eq!(id(&rx.recv().await.unwrap()), expected);
+        }
+        assert_eq!(rx.shared.lock().spilled, 0);
+        assert_eq!(rx.shared.lock_spill_file().as_ref().unwrap().pending, 0);
+
+        // Once drained, updates are buffered in memory again
+        tx.send(item(7)).await.unwrap();
+        tx.send(item(8)).await.unwrap();
+        assert_eq!(rx.shared.lock().items.len(), 2);
+        drop(tx);
+        assert_eq!(drain(&mut rx).await, [7, 8]);
+        assert_eq!(dropped.load(Ordering::Relaxed), 0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::await_holding_lock)]
+    async fn stalled_spill_file_does_not_block_the_queue() {
+        let dir = std::env::temp_dir().join(format!("jetstream-stall-{}", std::process::id()));
+        let policy = BackpressurePolicy::SpillToDisk { dir: dir.clone() };
+        let (tx, mut rx, dropped) = test_queue(2, policy);
+        let shared = rx.shared.clone();
+
+        // Hold the spill file as a stuck disk would while 2 and 3 overflow
+        let stalled = shared.lock_spill_file();
+        let producer = tokio::spawn(async move {
+            for n in 0..4 {
+                tx.send(item(n)).await.unwrap();
+            }
+        });
+        for expected in 0..2 {
+            let received = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await;
+            assert_eq!(id(&received.unwrap().unwrap()), expected);
+        }
+        assert_eq!(shared.lock().spilled, 1);
+        drop(stalled);
+
+        producer.await.unwrap();
+        assert_eq!(drain(&mut rx).await, [2, 3]);
+        assert_eq!(dropped.load(Ordering::Relaxed), 0);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn send_fails_once_receiver_is_dropped() {
+        let (tx, rx, _) = test_queue(1, BackpressurePolicy::Block);
+        tx.send(item(0)).await.unwrap();
+        let blocked = tokio::spawn({
+            let tx = tx.clone();
+            async move { tx.send(item(1)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(rx);
+        assert!(blocked.await.unwrap().is_err());
+        assert!(tx.send(item(2)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn recv_ends_after_last_sender_is_dropped() {
+        let (tx, mut rx, _) = test_queue(4, BackpressurePolicy::DropNewest);
+        let second = tx.clone();
+        tx.send(item(0)).await.unwrap();
+        drop(tx);
+        second.send(item(1)).await.unwrap();
+        let receiver = tokio::spawn(async move { drain(&mut rx).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(second);
+        assert_eq!(receiver.await.unwrap(), [0, 1]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+
+use jetstream_protos::jetstream::{subscribe_update::UpdateOneof, SubscribeUpdate};
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::service::interceptor::InterceptedService;
+use tonic::IntoStreamingRequest;
+
+use crate::client::ClientSettings;
+use crate::update::{proto_slot, RawUpdate, Update};
+
+use super::auth::Auth;
+use super::backpressure::{queue, QueueSender};
+use super::keepalive::Keepalive;
+use super::race::RaceTracker;
+use super::reconnect::{wait_before_reconnect, Backoff};
+use super::stream::{AbortOnDrop, UpdateStream, STREAM_BUFFER};
+use super::subscription::{log_account_filter, log_filter, SubscriptionHandle};
+use super::tap::{SubscribeCodec, SUBSCRIBE_PATH};
+use super::tls;
+
+/// Race every configured endpoint and stream the first arrival of each update.
+/// Every message received is also sent to `tap`, if set.
+pub(crate) fn jetstream_connector(
+    settings: Arc<ClientSettings>,
+    auth: Auth,
+    subscription: SubscriptionHandle,
+    tap: Option<mpsc::Sender<RawUpdate>>,
+) -> UpdateStream {
+    log::info!(
+        "Starting Jetstream connector with URL(s): {}",
+        settings.endpoints.join(", ")
+    );
+
+    let filters = subscription.filters();
+    log::info!(
+        "Using {} filter(s) for transaction filtering",
+        filters.len()
+    );
+
+    for (name, filter) in &filters {
+        log_filter(name, filter);
+    }
+    for (name, filter) in &subscription.account_filters() {
+        log_account_filter(name, filter);
+    }
+
+    let dropped = Arc::new(AtomicU64::new(0));
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    let merge_task = tokio::spawn(merge_endpoints(
+        settings,
+        auth,
+        subscription,
+        tap,
+        tx,
+        dropped.clone(),
+    ));
+
+    UpdateStream::new(rx, dropped, vec![AbortOnDrop::new(merge_task)])
+}
+
+/// Run one task per endpoint and forward deduplicated updates until every
+/// endpoint gives up or the stream is dropped
+async fn merge_endpoints(
+    settings: Arc<ClientSettings>,
+    auth: Auth,
+    subscription: SubscriptionHandle,
+    tap: Option<mpsc::Sender<RawUpdate>>,
+    tx: mpsc::Sender<anyhow::Result<Update>>,
+    dropped: Arc<AtomicU64>,
+) {
+    let refresh_task = auth
+        .spawn_refresh(settings.auth.refresh_interval)
+        .map(AbortOnDrop::new);
+
+    let endpoints = settings.endpoints.clone();
+    let (merged_tx, mut merged_rx) = queue::<(usize, SubscribeUpdate)>(
+        settings.channel_capacity,
+        &settings.backpressure,
+        "raw",
+        dropped,
+    );
+
+    let mut endpoint_tasks = Vec::with_capacity(endpoints.len());
+    for (index, url) in endpoints.iter().enumerate() {
+        let url = url.clone();
+        let settings = settings.clone();
+        let subscription = subscription.clone();
+        let auth = auth.clone();
+        let tap = tap.clone();
+        let merged_tx = merged_tx.clone();
+        endpoint_tasks.push(AbortOnDrop::new(tokio::spawn(async move {
+            endpoint_loop(
+                index,
+                &url,
+                &settings,
+                &auth,
+                &subscription,
+                tap.as_ref(),
+                &merged_tx,
+            )
+            .await
+        })));
+    }
+    drop(merged_tx);
+    drop(tap);
+
+    let mut race = RaceTracker::new(endpoints.clone(), settings.dedup_capacity);
+    let mut stats_interval = tokio::time::interval(settings.race_stats_interval);
+    stats_interval.tick().await;
+
+    loop {
+        tokio::select! {
+            update = merged_rx.recv() => {
+                let Some((index, msg)) = update else {
+                    break;
+                };
+                let Some(update) = Update::from_proto(index, msg) else {
+                    continue;
+                };
+                if race.first_arrival(index, &update.dedup_key())
+                    && tx.send(Ok(update)).await.is_err()
+                {
+                    log::info!("Jetstream update stream dropped, stopping connector");
+                    return;
+                }
+            }
+            _ = stats_interval.tick(), if endpoints.len() > 1 => {
+                race.log_stats();
+            }
+        }
+    }
+
+    if endpoints.len() > 1 {
+        race.log_stats();
+    }
+    drop(refresh_task);
+
+    // Every endpoint gave up; surface the first failure
+    for task in endpoint_tasks {
+        let error = match task.await {
+            Ok(Ok(())) => continue,
+            Ok(Err(e)) => e,
+            Err(e) => anyhow::anyhow!("Endpoint task panicked: {}", e),
+        };
+        let _ = tx.send(Err(error)).await;
+        return;
+    }
+
+    log::info!("Jetstream connector stream ended");
+}
+
+/// Keep a single endpoint subscribed, reconnecting with backoff until the
+/// attempt budget is exhausted
+async fn endpoint_loop(
+    index: usize,
+    url: &str,
+    settings: &ClientSettings,
+    auth: &Auth,
+    subscription: &SubscriptionHandle,
+    tap: Option<&mpsc::Sender<RawUpdate>>,
+    merged_tx: &QueueSender<(usize, SubscribeUpdate)>,
+) -> anyhow::Result<()> {
+    let mut backoff = Backoff::new(&settings.reconnect);
+
+    loop {
+        match run_session(index, url, settings, auth, subscription, tap, merged_tx).await {
+            Ok(received) => {
+                log::info!(
+                    "Jetstream connector stream from {} ended after {} update(s)",
+                    url,
+                    received
+                );
+                if received > 0 {
+                    backoff.reset();
+                }
+            }
+            Err(e) => log::warn!("Jetstream connector session to {} failed: {:#}", url, e),
+        }
+
+        wait_before_reconnect(&mut backoff, url).await?;
+    }
+}
+
+/// Connect, subscribe and forward updates until the stream ends.
+/// Returns the number of transaction and account updates received during the session.
+async fn run_session(
+    index: usize,
+    url: &str,
+    settings: &ClientSettings,
+    auth: &Auth,
+    subscription: &SubscriptionHandle,
+    tap: Option<&mpsc::Sender<RawUpdate>>,
+    merged_tx: &QueueSender<(usize, SubscribeUpdate)>,
+) -> anyhow::Result<u64> {
+    let channel = tls::connect(settings, url).await?;
+
+    // The generated client's codec decodes updates without keeping their bytes
+    let mut client = tonic::client::Grpc::new(InterceptedService::new(channel, auth.clone()));
+    client
+        .ready()
+        .await
+        .map_err(|e| anyhow::anyhow!("Jetstream service not ready: {}", e))?;
+
+    log::info!("Jetstream connector connected successfully to {}", url);
+
+    let (mut keepalive, pings) = Keepalive::new(&settings.keepalive);
+    let outbound = subscription.attach().merge(pings);
+    let response = client
+        .streaming(
+            outbound.into_streaming_request(),
+            PathAndQuery::from_static(SUBSCRIBE_PATH),
+            SubscribeCodec::new(tap.is_some()),
+        )
+        .await?;
+    let mut inbound = response.into_inner();
+
+    let mut ticker = keepalive.ticker();
+    let mut received = 0u64;
+
+    let result = loop {
+        tokio::select! {
+            response = inbound.next() => {
+                let Some(response) = response else {
+                    break Ok(received);
+                };
+                let (msg, raw) = match response {
+                    Ok(response) => response,
+                    Err(status) => {
+                        log::warn!("Error receiving update from {}: {}", url, status);
+                        break Ok(received);
+                    }
+                };
+
+                if let (Some(tap), Some(bytes)) = (tap, raw) {
+                    let raw = RawUpdate {
+                        endpoint: index,
+                        bytes,
+                        slot: proto_slot(&msg),
+                    };
+                    // A dropped tap receiver stops the tap, not the subscription
+                    let _ = tap.send(raw).await;
+                }
+
+                // Keepalive traffic alone does not make a session healthy
+                match &msg.update_oneof {
+                    Some(UpdateOneof::Ping(_)) => {
+                        if let Err(e) = keepalive.on_server_ping().await {
+                            break Err(e);
+                        }
+                        continue;
+                    }
+                    Some(UpdateOneof::Pong(pong)) => {
+                        keepalive.on_pong(pong.id);
+                        continue;
+                    }
+                    Some(UpdateOneof::Transaction(_) | UpdateOneof::Account(_)) => received += 1,
+                    None => {}
+                }
+
+                // Forward inline so updates keep the order they arrived in
+                if merged_tx.send((index, msg)).await.is_err() {
+                    break Ok(received);
+                }
+            }
+            _ = ticker.tick(), if keepalive.enabled() => {
+                if let Err(e) = keepalive.ping().await {
+                    break Err(e);
+                }
+            }
+        }
+    };
+
+    if let Some(rtt) = keepalive.last_rtt() {
+        log::info!("Last ping round-trip time to {}: {:?}", url, rtt);
+    }
+
+    result
+}
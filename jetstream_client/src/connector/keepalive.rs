@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+
+use jetstream_protos::jetstream::{SubscribeRequest, SubscribeRequestPing};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant, Interval, MissedTickBehavior};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::config::KeepaliveConfig;
+
+/// Id 1 is used by the initial request and replies to server pings
+const PERIODIC_PING_FIRST_ID: i32 = 2;
+
+/// Ping/pong bookkeeping for a single `Subscribe` stream.
+///
+/// Replies to server pings, sends periodic client pings and measures their
+/// round-trip time from the matching `SubscribeUpdatePong.id`. The session is
+/// considered dead once `max_missed` consecutive pings go unanswered.
+#[derive(Debug)]
+pub struct Keepalive {
+    tx: mpsc::Sender<SubscribeRequest>,
+    interval: Duration,
+    max_missed: u32,
+    next_id: i32,
+    outstanding: VecDeque<(i32, Instant)>,
+    missed: u32,
+    last_rtt: Option<Duration>,
+}
+
+impl Keepalive {
+    /// Create the keepalive and the outbound stream its pings are sent on
+    pub fn new(config: &KeepaliveConfig) -> (Self, ReceiverStream<SubscribeRequest>) {
+        let (tx, rx) = mpsc::channel(16);
+        let keepalive = Self {
+            tx,
+            interval: config.interval,
+            max_missed: config.max_missed.max(1),
+            next_id: PERIODIC_PING_FIRST_ID,
+            outstanding: VecDeque::new(),
+            missed: 0,
+            last_rtt: None,
+        };
+        (keepalive, ReceiverStream::new(rx))
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.interval.is_zero()
+    }
+
+    /// Ticker driving periodic pings
+    pub fn ticker(&self) -> Interval {
+        ping_ticker(self.interval)
+    }
+
+    /// Send a periodic ping, failing if too many previous pings went unanswered
+    pub async fn ping(&mut self) -> anyhow::Result<()> {
+        if !self.outstanding.is_empty() {
+            self.missed += 1;
+            log::warn!(
+                "Missed pong ({} consecutive, limit {})",
+                self.missed,
+                self.max_missed
+            );
+            if self.missed >= self.max_missed {
+                return Err(anyhow::anyhow!(
+                    "No pong received for {} ping(s), connection considered dead",
+                    self.missed
+                ));
+            }
+        }
+
+        let id = self.next_id;
+        self.next_id = self
+            .next_id
+            .checked_add(1)
+            .unwrap_or(PERIODIC_PING_FIRST_ID);
+        self.outstanding.push_back((id, Instant::now()));
+        self.send(id).await
+    }
+
+    /// Answer a server `SubscribeUpdatePing`
+    pub async fn on_server_ping(&mut self) -> anyhow::Result<()> {
+        log::debug!("Ping from server, replying");
+        self.send(1).await
+    }
+
+    /// Record a `SubscribeUpdatePong`
+    pub fn on_pong(&mut self, id: i32) {
+        let Some(position) = self.outstanding.iter().position(|(sent, _)| *sent == id) else {
+            log::debug!("Pong {} does not match an outstanding ping", id);
+            return;
+        };
+
+        let (_, sent_at) = self.outstanding[position];
+        // Anything older than the answered ping will never be answered
+        self.outstanding.drain(..=position);
+        let rtt = sent_at.elapsed();
+        self.last_rtt = Some(rtt);
+        self.missed = 0;
+        log::debug!("Pong {} received, round-trip time {:?}", id, rtt);
+    }
+
+    /// Round-trip time of the most recently answered ping
+    pub fn last_rtt(&self) -> Option<Duration> {
+        self.last_rtt
+    }
+
+    async fn send(&self, id: i32) -> anyhow::Result<()> {
+        let request = SubscribeRequest {
+            transactions: Default::default(),
+            accounts: Default::default(),
+            ping: Some(SubscribeRequestPing { id }),
+        };
+        self.tx
+            .send(request)
+            .await
+            .map_err(|_| anyhow::anyhow!("Subscribe request stream closed"))
+    }
+}
+
+/// Ticker for periodic pings; fires hourly when `interval` is zero so callers
+/// can guard the branch instead of special-casing a disabled keepalive
+pub fn ping_ticker(interval: Duration) -> Interval {
+    let period = if interval.is_zero() {
+        Duration::from_secs(3600)
+    } else {
+        interval
+    };
+    let mut ticker = tokio::time::interval_at(Instant::now() + period, period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticker
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use super::*;
+
+    fn keepalive(max_missed: u32) -> (Keepalive, ReceiverStream<SubscribeRequest>) {
+        Keepalive::new(&KeepaliveConfig {
+            interval: Duration::from_secs(1),
+            max_missed,
+        })
+    }
+
+    async fn next_ping_id(pings: &mut ReceiverStream<SubscribeRequest>) -> i32 {
+        pings.next().await.unwrap().ping.unwrap().id
+    }
+
+    #[tokio::test]
+    async fn unanswered_pings_kill_the_session() {
+        let (mut keepalive, _pings) = keepalive(2);
+        keepalive.ping().await.unwrap();
+        keepalive.ping().await.unwrap();
+        let err = keepalive.ping().await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No pong received for 2 ping(s), connection considered dead"
+        );
+    }
+
+    #[tokio::test]
+    async fn pong_resets_missed_count() {
+        let (mut keepalive, mut pings) = keepalive(2);
+        keepalive.ping().await.unwrap();
+        keepalive.ping().await.unwrap();
+        next_ping_id(&mut pings).await;
+        let id = next_ping_id(&mut pings).await;
+
+        keepalive.on_pong(id);
+        assert_eq!(keepalive.missed, 0);
+        assert!(keepalive.outstanding.is_empty());
+        keepalive.ping().await.unwrap();
+        keepalive.ping().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn tracks_round_trip_time_of_matching_pong() {
+        let (mut keepalive, mut pings) = keepalive(3);
+        assert_eq!(keepalive.last_rtt(), None);
+
+        keepalive.ping().await.unwrap();
+        let id = next_ping_id(&mut pings).await;
+        assert_eq!(id, PERIODIC_PING_FIRST_ID);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        keepalive.on_pong(id + 100);
+        assert_eq!(keepalive.last_rtt(), None);
+
+        keepalive.on_pong(id);
+        let rtt = keepalive.last_rtt().unwrap();
+        assert!(rtt >= Duration::from_millis(20), "{:?}", rtt);
+        assert!(rtt < Duration::from_secs(5), "{:?}", rtt);
+    }
+
+    #[tokio::test]
+    async fn server_pings_are_answered_with_reserved_id() {
+        let (mut keepalive, mut pings) = keepalive(3);
+        keepalive.on_server_ping().await.unwrap();
+        assert_eq!(next_ping_id(&mut pings).await, 1);
+        assert!(keepalive.outstanding.is_empty());
+    }
+}
@@ -0,0 +1,12 @@
+pub mod auth;
+pub mod backpressure;
+#[allow(clippy::module_inception)]
+pub mod connector;
+pub mod keepalive;
+pub mod parsed;
+pub mod race;
+pub mod reconnect;
+pub mod stream;
+pub mod subscription;
+pub mod tap;
+pub mod tls;
@@ -0,0 +1,203 @@
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+
+use jetstream_protos::jetstream::{
+    SubscribeParsedRequest, SubscribeRequestPing, SubscribeUpdateParsedTransaction,
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::service::interceptor::InterceptedService;
+use tonic::IntoStreamingRequest;
+
+use crate::client::ClientSettings;
+use crate::update::RawUpdate;
+
+use super::auth::Auth;
+use super::backpressure::{queue, QueueSender};
+use super::keepalive::ping_ticker;
+use super::reconnect::{wait_before_reconnect, Backoff};
+use super::stream::{AbortOnDrop, ParsedUpdateStream, STREAM_BUFFER};
+use super::tap::{SubscribeParsedCodec, SUBSCRIBE_PARSED_PATH};
+use super::tls;
+
+type ParsedSender = QueueSender<SubscribeUpdateParsedTransaction>;
+
+/// How a single parsed subscription session ended
+enum SessionEnd {
+    Shutdown,
+    StreamEnded { received: u64 },
+}
+
+/// Stream parsed transactions from the first configured endpoint.
+/// Every message received is also sent to `tap`, if set.
+pub(crate) fn jetstream_parsed_connector(
+    settings: Arc<ClientSettings>,
+    auth: Auth,
+    tap: Option<mpsc::Sender<RawUpdate>>,
+) -> ParsedUpdateStream {
+    let dropped = Arc::new(AtomicU64::new(0));
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    let (queue_tx, mut queue_rx) = queue(
+        settings.channel_capacity,
+        &settings.backpressure,
+        "parsed",
+        dropped.clone(),
+    );
+
+    let task = tokio::spawn(async move {
+        let session_task = AbortOnDrop::new(tokio::spawn(async move {
+            parsed_loop(&settings, &auth, tap.as_ref(), &queue_tx).await
+        }));
+
+        while let Some(parsed_tx) = queue_rx.recv().await {
+            if tx.send(Ok(parsed_tx)).await.is_err() {
+                return;
+            }
+        }
+
+        let error = match session_task.await {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e,
+            Err(e) => anyhow::anyhow!("Parsed connector task panicked: {}", e),
+        };
+        let _ = tx.send(Err(error)).await;
+    });
+
+    ParsedUpdateStream::new(rx, dropped, vec![AbortOnDrop::new(task)])
+}
+
+async fn parsed_loop(
+    settings: &ClientSettings,
+    auth: &Auth,
+    tap: Option<&mpsc::Sender<RawUpdate>>,
+    tx: &ParsedSender,
+) -> anyhow::Result<()> {
+    let url = settings
+        .endpoints
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("No Jetstream gRPC URL configured"))?;
+    if settings.endpoints.len() > 1 {
+        log::warn!(
+            "Parsed streaming does not race endpoints, only {} will be used",
+            url
+        );
+    }
+
+    let _refresh_task = auth
+        .spawn_refresh(settings.auth.refresh_interval)
+        .map(AbortOnDrop::new);
+
+    let mut backoff = Backoff::new(&settings.reconnect);
+
+    loop {
+        match run_session(settings, url, auth, tap, tx).await {
+            Ok(SessionEnd::Shutdown) => {
+                log::info!("Jetstream parsed stream dropped, stopping connector");
+                return Ok(());
+            }
+            Ok(SessionEnd::StreamEnded { received }) => {
+                log::info!(
+                    "Jetstream parsed stream ended after {} message(s)",
+                    received
+                );
+                if received > 0 {
+                    backoff.reset();
+                }
+            }
+            Err(e) => log::warn!("Jetstream parsed connector session failed: {:#}", e),
+        }
+
+        wait_before_reconnect(&mut backoff, url).await?;
+    }
+}
+
+/// Connect and stream parsed transactions until the stream ends or the receiver is dropped
+async fn run_session(
+    settings: &ClientSettings,
+    url: &str,
+    auth: &Auth,
+    tap: Option<&mpsc::Sender<RawUpdate>>,
+    tx: &ParsedSender,
+) -> anyhow::Result<SessionEnd> {
+    let channel = tls::connect(settings, url).await?;
+
+    // The generated client's codec decodes transactions without keeping their bytes
+    let mut client = tonic::client::Grpc::new(InterceptedService::new(channel, auth.clone()));
+    client
+        .ready()
+        .await
+        .map_err(|e| anyhow::anyhow!("Jetstream service not ready: {}", e))?;
+
+    log::info!("Jetstream parsed connector connected successfully");
+
+    let request = SubscribeParsedRequest {
+        ping: Some(SubscribeRequestPing { id: 1 }),
+    };
+
+    // The parsed stream carries no pongs, so pings only keep the stream active
+    let (ping_tx, ping_rx) = mpsc::channel(16);
+    let _ = ping_tx.try_send(request);
+    let mut ticker = ping_ticker(settings.keepalive.interval);
+    let mut next_ping_id = 2;
+
+    let outbound = ReceiverStream::new(ping_rx);
+    let response = client
+        .streaming(
+            outbound.into_streaming_request(),
+            PathAndQuery::from_static(SUBSCRIBE_PARSED_PATH),
+            SubscribeParsedCodec::new(tap.is_some()),
+        )
+        .await?;
+
+    let mut inbound = response.into_inner();
+
+    log::info!("Starting to receive parsed transactions...");
+
+    let mut received = 0u64;
+
+    let end = loop {
+        tokio::select! {
+            response = inbound.next() => {
+                match response {
+                    Some(Ok((parsed_tx, raw))) => {
+                        received += 1;
+                        if let (Some(tap), Some(bytes)) = (tap, raw) {
+                            let raw = RawUpdate {
+                                endpoint: 0,
+                                bytes,
+                                slot: Some(parsed_tx.slot),
+                            };
+                            // A dropped tap receiver stops the tap, not the subscription
+                            let _ = tap.send(raw).await;
+                        }
+                        if tx.send(parsed_tx).await.is_err() {
+                            break SessionEnd::Shutdown;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        log::warn!("Error receiving parsed transaction: {}", e);
+                        break SessionEnd::StreamEnded { received };
+                    }
+                    None => {
+                        log::info!("Parsed stream ended");
+                        break SessionEnd::StreamEnded { received };
+                    }
+                }
+            }
+            _ = ticker.tick(), if !settings.keepalive.interval.is_zero() => {
+                let ping = SubscribeParsedRequest {
+                    ping: Some(SubscribeRequestPing { id: next_ping_id }),
+                };
+                next_ping_id = next_ping_id.checked_add(1).unwrap_or(1);
+                if ping_tx.send(ping).await.is_err() {
+                    log::warn!("Parsed request stream closed");
+                    break SessionEnd::StreamEnded { received };
+                }
+            }
+        }
+    };
+
+    Ok(end)
+}
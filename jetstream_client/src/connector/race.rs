@@ -0,0 +1,111 @@
+use std::collections::{HashSet, VecDeque};
+
+/// Deduplicates updates arriving from several endpoints and counts
+/// which endpoint delivered each one first
+#[derive(Debug)]
+pub struct RaceTracker {
+    endpoints: Vec<String>,
+    wins: Vec<u64>,
+    duplicates: u64,
+    seen: HashSet<Vec<u8>>,
+    order: VecDeque<Vec<u8>>,
+    capacity: usize,
+}
+
+impl RaceTracker {
+    pub fn new(endpoints: Vec<String>, capacity: usize) -> Self {
+        let wins = vec![0; endpoints.len()];
+        Self {
+            endpoints,
+            wins,
+            duplicates: 0,
+            seen: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Returns true if this is the first time `key` (a transaction signature or
+    /// account pubkey + write version) has been seen, crediting the win to `endpoint`
+    pub fn first_arrival(&mut self, endpoint: usize, key: &[u8]) -> bool {
+        if self.seen.contains(key) {
+            self.duplicates += 1;
+            return false;
+        }
+
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.seen.insert(key.to_vec());
+        self.order.push_back(key.to_vec());
+
+        if let Some(wins) = self.wins.get_mut(endpoint) {
+            *wins += 1;
+        }
+        true
+    }
+
+    pub fn log_stats(&self) {
+        let total: u64 = self.wins.iter().sum();
+        log::info!(
+            "Race stats - {} unique update(s), {} duplicate(s) dropped",
+            total,
+            self.duplicates
+        );
+        for (endpoint, wins) in self.endpoints.iter().zip(&self.wins) {
+            let share = if total > 0 {
+                *wins as f64 * 100.0 / total as f64
+            } else {
+                0.0
+            };
+            log::info!("  {}: {} win(s) ({:.1}%)", endpoint, wins, share);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(capacity: usize) -> RaceTracker {
+        RaceTracker::new(vec!["a".to_string(), "b".to_string()], capacity)
+    }
+
+    #[test]
+    fn first_endpoint_wins_and_duplicates_are_dropped() {
+        let mut race = tracker(16);
+        assert!(race.first_arrival(1, b"sig1"));
+        assert!(!race.first_arrival(0, b"sig1"));
+        assert!(race.first_arrival(0, b"sig2"));
+        assert!(!race.first_arrival(1, b"sig2"));
+        assert!(!race.first_arrival(1, b"sig2"));
+        assert!(race.first_arrival(1, b"sig3"));
+
+        assert_eq!(race.wins, [1, 2]);
+        assert_eq!(race.duplicates, 3);
+    }
+
+    #[test]
+    fn unknown_endpoint_is_not_credited() {
+        let mut race = tracker(16);
+        assert!(race.first_arrival(5, b"sig"));
+        assert_eq!(race.wins, [0, 0]);
+    }
+
+    #[test]
+    fn oldest_keys_are_evicted_past_capacity() {
+        let mut race = tracker(2);
+        assert!(race.first_arrival(0, b"sig1"));
+        assert!(race.first_arrival(0, b"sig2"));
+        assert!(race.first_arrival(0, b"sig3"));
+        assert_eq!(race.seen.len(), 2);
+
+        // sig1 fell out of the window, so a late copy counts as new
+        assert!(race.first_arrival(1, b"sig1"));
+        // sig3 is still remembered
+        assert!(!race.first_arrival(1, b"sig3"));
+        assert_eq!(race.wins, [3, 1]);
+    }
+}
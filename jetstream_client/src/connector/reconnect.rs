@@ -0,0 +1,172 @@
+use rand::Rng;
+use tokio::time::Duration;
+
+use crate::config::ReconnectConfig;
+
+/// Exponential backoff schedule used between reconnect attempts
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    multiplier: f64,
+    jitter: f64,
+    max_attempts: Option<u32>,
+    attempt: u32,
+    reconnects: u64,
+}
+
+impl Backoff {
+    pub fn new(config: &ReconnectConfig) -> Self {
+        Self {
+            initial: config.initial_backoff,
+            max: config.max_backoff,
+            multiplier: config.multiplier.max(1.0),
+            jitter: config.jitter.clamp(0.0, 1.0),
+            max_attempts: config.max_attempts,
+            attempt: 0,
+            reconnects: 0,
+        }
+    }
+
+    /// Delay before the next attempt, or `None` once `max_attempts` is exhausted
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        if let Some(max_attempts) = self.max_attempts {
+            if self.attempt >= max_attempts {
+                return None;
+            }
+        }
+
+        let exp = self
+            .multiplier
+            .powi(self.attempt.min(i32::MAX as u32) as i32);
+        let base =
+            Duration::from_secs_f64((self.initial.as_secs_f64() * exp).min(self.max.as_secs_f64()));
+        let delay = if self.jitter > 0.0 {
+            let factor = rand::thread_rng().gen_range(1.0 - self.jitter..=1.0 + self.jitter);
+            base.mul_f64(factor).min(self.max)
+        } else {
+            base
+        };
+
+        self.attempt += 1;
+        self.reconnects += 1;
+        Some(delay)
+    }
+
+    /// Reset the schedule after a session that delivered data
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Total reconnects since the connector started
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects
+    }
+}
+
+/// Wait before reconnecting, failing once the attempt budget is exhausted
+pub async fn wait_before_reconnect(backoff: &mut Backoff, url: &str) -> anyhow::Result<()> {
+    let Some(delay) = backoff.next_delay() else {
+        return Err(anyhow::anyhow!(
+            "Giving up on {} after {} reconnect attempt(s)",
+            url,
+            backoff.attempt()
+        ));
+    };
+
+    log::warn!(
+        "Reconnecting to {} in {:?} (attempt {}, total reconnects: {})",
+        url,
+        delay,
+        backoff.attempt(),
+        backoff.reconnects()
+    );
+    tokio::time::sleep(delay).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_attempts: Option<u32>, jitter: f64) -> ReconnectConfig {
+        ReconnectConfig {
+            max_attempts,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            multiplier: 2.0,
+            jitter,
+        }
+    }
+
+    #[test]
+    fn grows_exponentially_up_to_max() {
+        let mut backoff = Backoff::new(&config(None, 0.0));
+        let delays: Vec<_> = (0..6).map(|_| backoff.next_delay().unwrap()).collect();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis)
+        );
+        assert_eq!(backoff.reconnects(), 6);
+    }
+
+    #[test]
+    fn reset_restarts_schedule_but_keeps_reconnect_count() {
+        let mut backoff = Backoff::new(&config(None, 0.0));
+        backoff.next_delay();
+        backoff.next_delay();
+        backoff.reset();
+        assert_eq!(backoff.attempt(), 0);
+        assert_eq!(backoff.next_delay(), Some(Duration::from_millis(100)));
+        assert_eq!(backoff.reconnects(), 3);
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let mut backoff = Backoff::new(&config(None, 0.2));
+        for _ in 0..200 {
+            backoff.reset();
+            let delay = backoff.next_delay().unwrap();
+            assert!(delay >= Duration::from_millis(80), "{:?}", delay);
+            assert!(delay <= Duration::from_millis(120), "{:?}", delay);
+        }
+        for _ in 0..20 {
+            assert!(backoff.next_delay().unwrap() <= Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut backoff = Backoff::new(&config(Some(3), 0.0));
+        assert!(backoff.next_delay().is_some());
+        assert!(backoff.next_delay().is_some());
+        assert!(backoff.next_delay().is_some());
+        assert_eq!(backoff.next_delay(), None);
+        assert_eq!(backoff.attempt(), 3);
+
+        let mut disabled = Backoff::new(&config(Some(0), 0.0));
+        assert_eq!(disabled.next_delay(), None);
+    }
+
+    #[tokio::test]
+    async fn wait_fails_once_attempts_are_exhausted() {
+        let mut backoff = Backoff::new(&ReconnectConfig {
+            initial_backoff: Duration::from_millis(1),
+            ..config(Some(1), 0.0)
+        });
+        wait_before_reconnect(&mut backoff, "http://a")
+            .await
+            .unwrap();
+        let err = wait_before_reconnect(&mut backoff, "http://a")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Giving up on http://a after 1 reconnect attempt(s)"
+        );
+    }
+}
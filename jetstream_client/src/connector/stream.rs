@@ -0,0 +1,171 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::StreamExt;
+use jetstream_protos::jetstream::SubscribeUpdateParsedTransaction;
+use tokio::sync::mpsc;
+use tokio::task::{JoinError, JoinHandle};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+
+use crate::update::Update;
+
+/// Stream of deduplicated updates returned by [`crate::JetstreamClient::subscribe`]
+pub type UpdateStream = TaskStream<Update>;
+
+/// Stream of parsed transactions returned by [`crate::JetstreamClient::subscribe_parsed`]
+pub type ParsedUpdateStream = TaskStream<SubscribeUpdateParsedTransaction>;
+
+/// Updates buffered between the connector tasks and the stream; the
+/// backpressure queue in front of it holds the configured capacity
+pub(crate) const STREAM_BUFFER: usize = 128;
+
+/// Task handle that aborts the task when dropped
+#[derive(Debug)]
+pub(crate) struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> AbortOnDrop<T> {
+    pub fn new(handle: JoinHandle<T>) -> Self {
+        Self(handle)
+    }
+}
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Stream fed by background tasks; dropping it stops the tasks.
+///
+/// Yields `Err` once when the subscription gives up, e.g. after every
+/// endpoint exhausted its reconnect attempts, and ends afterwards.
+#[derive(Debug)]
+pub struct TaskStream<T> {
+    rx: ReceiverStream<anyhow::Result<T>>,
+    dropped: Arc<AtomicU64>,
+    _tasks: Vec<AbortOnDrop<()>>,
+}
+
+impl<T> TaskStream<T> {
+    pub(crate) fn new(
+        rx: mpsc::Receiver<anyhow::Result<T>>,
+        dropped: Arc<AtomicU64>,
+        tasks: Vec<AbortOnDrop<()>>,
+    ) -> Self {
+        Self {
+            rx: ReceiverStream::new(rx),
+            dropped,
+            _tasks: tasks,
+        }
+    }
+
+    /// Number of updates discarded by the backpressure policy so far
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Shared counter behind [`TaskStream::dropped`], readable after the stream is consumed
+    pub fn dropped_counter(&self) -> Arc<AtomicU64> {
+        self.dropped.clone()
+    }
+}
+
+impl<T: Send + 'static> TaskStream<T> {
+    /// Run `decode` on up to `concurrency` items at once, yielding the results
+    /// in the order the items arrived. A concurrency of 1 decodes inline.
+    pub fn decode_ordered<R, F>(
+        self,
+        concurrency: usize,
+        decode: F,
+    ) -> impl Stream<Item = anyhow::Result<R>> + Send
+    where
+        R: Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        self.decode_ordered_with(concurrency, |_| {}, decode)
+    }
+
+    /// Like [`TaskStream::decode_ordered`], but first runs `prepare` on each item
+    /// one at a time in arrival order, so state it updates (e.g. lookup tables
+    /// from account updates) is in place before any later item is decoded
+    pub fn decode_ordered_with<R, P, F>(
+        self,
+        concurrency: usize,
+        mut prepare: P,
+        decode: F,
+    ) -> impl Stream<Item = anyhow::Result<R>> + Send
+    where
+        R: Send + 'static,
+        P: FnMut(&T) + Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        let decode = Arc::new(decode);
+        let concurrency = concurrency.max(1);
+        self.map(move |item| {
+            if let Ok(item) = &item {
+                prepare(item);
+            }
+            let decode = decode.clone();
+            async move {
+                let item = item?;
+                if concurrency == 1 {
+                    return Ok(decode(item));
+                }
+                tokio::spawn(async move { decode(item) })
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Decode task panicked: {}", e))
+            }
+        })
+        .buffered(concurrency)
+    }
+}
+
+impl<T> Stream for TaskStream<T> {
+    type Item = anyhow::Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.rx).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn prepare_runs_in_order_before_later_items_decode() {
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        for n in 0..100u64 {
+            tx.send(Ok(n)).await.unwrap();
+        }
+        drop(tx);
+
+        let prepared = Arc::new(AtomicU64::new(0));
+        let counter = prepared.clone();
+        let stream = TaskStream::new(rx, Arc::default(), Vec::new()).decode_ordered_with(
+            8,
+            move |n: &u64| {
+                assert_eq!(counter.fetch_add(1, Ordering::SeqCst), *n);
+            },
+            move |n| {
+                // Every earlier item, and this one, was prepared before decoding
+                assert!(prepared.load(Ordering::SeqCst) > n);
+                n * 2
+            },
+        );
+        let decoded: Vec<u64> = stream.map(|result| result.unwrap()).collect().await;
+        assert_eq!(decoded, (0..100).map(|n| n * 2).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use jetstream_protos::jetstream::{
+    SubscribeRequest, SubscribeRequestFilterAccounts, SubscribeRequestFilterTransactions,
+    SubscribeRequestPing,
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+type TransactionFilters = HashMap<String, SubscribeRequestFilterTransactions>;
+type AccountFilters = HashMap<String, SubscribeRequestFilterAccounts>;
+
+/// Handle for changing the filters of a live subscription.
+///
+/// Every open `Subscribe` stream created through [`SubscriptionHandle::attach`]
+/// receives the full updated `SubscribeRequest` whenever the filters change,
+/// and reconnecting sessions start from the latest filters.
+#[derive(Debug, Clone)]
+pub struct SubscriptionHandle {
+    filters: Arc<Mutex<TransactionFilters>>,
+    accounts: Arc<Mutex<AccountFilters>>,
+    senders: Arc<Mutex<Vec<mpsc::Sender<SubscribeRequest>>>>,
+}
+
+impl SubscriptionHandle {
+    pub fn new(filters: TransactionFilters, accounts: AccountFilters) -> Self {
+        Self {
+            filters: Arc::new(Mutex::new(filters)),
+            accounts: Arc::new(Mutex::new(accounts)),
+            senders: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Current transaction filters
+    pub fn filters(&self) -> TransactionFilters {
+        self.filters.lock().expect("filters lock poisoned").clone()
+    }
+
+    /// Current account filters
+    pub fn account_filters(&self) -> AccountFilters {
+        self.accounts
+            .lock()
+            .expect("accounts lock poisoned")
+            .clone()
+    }
+
+    /// Replace all account filters
+    pub async fn set_account_filters(&self, accounts: AccountFilters) {
+        *self.accounts.lock().expect("accounts lock poisoned") = accounts;
+        self.broadcast().await;
+    }
+
+    /// Add a new named filter, failing if the name is already in use
+    pub async fn add_filter(
+        &self,
+        name: impl Into<String>,
+        filter: SubscribeRequestFilterTransactions,
+    ) -> anyhow::Result<()> {
+        let name = name.into();
+        {
+            let mut filters = self.filters.lock().expect("filters lock poisoned");
+            if filters.contains_key(&name) {
+                return Err(anyhow::anyhow!("Filter '{}' already exists", name));
+            }
+            filters.insert(name, filter);
+        }
+        self.broadcast().await;
+        Ok(())
+    }
+
+    /// Insert or overwrite a named filter
+    pub async fn replace_filter(
+        &self,
+        name: impl Into<String>,
+        filter: SubscribeRequestFilterTransactions,
+    ) {
+        self.filters
+            .lock()
+            .expect("filters lock poisoned")
+            .insert(name.into(), filter);
+        self.broadcast().await;
+    }
+
+    /// Remove a named filter. Returns false if no such filter existed.
+    pub async fn remove_filter(&self, name: &str) -> bool {
+        let removed = self
+            .filters
+            .lock()
+            .expect("filters lock poisoned")
+            .remove(name)
+            .is_some();
+        if removed {
+            self.broadcast().await;
+        }
+        removed
+    }
+
+    /// Apply a complete new set of filters, sending a single update with all changes
+    pub async fn update_filters(
+        &self,
+        transactions: TransactionFilters,
+        accounts: AccountFilters,
+    ) -> anyhow::Result<()> {
+        let changed = {
+            let mut filters = self.filters.lock().expect("filters lock poisoned");
+            let mut account_filters = self.accounts.lock().expect("accounts lock poisoned");
+            let mut changed = false;
+
+            for name in filters.keys() {
+                if !transactions.contains_key(name) {
+                    log::info!("Removing filter '{}'", name);
+                    changed = true;
+                }
+            }
+            for (name, filter) in &transactions {
+                match filters.get(name) {
+                    None => log::info!("Adding filter '{}'", name),
+                    Some(existing) if existing != filter => {
+                        log::info!("Replacing filter '{}'", name)
+                    }
+                    Some(_) => continue,
+                }
+                log_filter(name, filter);
+                changed = true;
+            }
+            *filters = transactions;
+
+            if accounts != *account_filters {
+                log::info!("Replacing {} account filter(s)", accounts.len());
+                *account_filters = accounts;
+                changed = true;
+            }
+            changed
+        };
+
+        if changed {
+            self.broadcast().await;
+        }
+        Ok(())
+    }
+
+    /// Open the outbound side of a new `Subscribe` stream, primed with the current filters
+    pub fn attach(&self) -> ReceiverStream<SubscribeRequest> {
+        let (tx, rx) = mpsc::channel(16);
+        let mut request = self.request();
+        request.ping = Some(SubscribeRequestPing { id: 1 });
+        // A fresh channel always has room for the initial request
+        let _ = tx.try_send(request);
+
+        let mut senders = self.senders.lock().expect("senders lock poisoned");
+        senders.retain(|sender| !sender.is_closed());
+        senders.push(tx);
+
+        ReceiverStream::new(rx)
+    }
+
+    fn request(&self) -> SubscribeRequest {
+        SubscribeRequest {
+            transactions: self.filters(),
+            accounts: self.account_filters(),
+            ping: None,
+        }
+    }
+
+    /// Push the current filters to every open stream
+    async fn broadcast(&self) {
+        let request = self.request();
+        let senders: Vec<_> = {
+            let mut senders = self.senders.lock().expect("senders lock poisoned");
+            senders.retain(|sender| !sender.is_closed());
+            senders.clone()
+        };
+
+        for sender in senders {
+            if sender.send(request.clone()).await.is_err() {
+                log::debug!("Subscription stream closed before filter update");
+            }
+        }
+        log::info!(
+            "Sent filter update with {} transaction and {} account filter(s) to open stream(s)",
+            request.transactions.len(),
+            request.accounts.len()
+        );
+    }
+}
+
+pub(crate) fn log_account_filter(name: &str, filter: &SubscribeRequestFilterAccounts) {
+    log::info!("Account filter '{}' configuration:", name);
+    if !filter.account.is_empty() {
+        log::info!("  Accounts: {}", filter.account.join(", "));
+    }
+    if !filter.owner.is_empty() {
+        log::info!("  Owners: {}", filter.owner.join(", "));
+    }
+    if !filter.filters.is_empty() {
+        log::info!("  {} data filter(s)", filter.filters.len());
+    }
+}
+
+pub(crate) fn log_filter(name: &str, filter: &SubscribeRequestFilterTransactions) {
+    log::info!("Filter '{}' configuration:", name);
+    if !filter.account_include.is_empty() {
+        log::info!("  Include accounts: {}", filter.account_include.join(", "));
+    }
+    if !filter.account_exclude.is_empty() {
+        log::info!("  Exclude accounts: {}", filter.account_exclude.join(", "));
+    }
+    if !filter.account_required.is_empty() {
+        log::info!(
+            "  Required accounts: {}",
+            filter.account_required.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(account: &str) -> SubscribeRequestFilterTransactions {
+        SubscribeRequestFilterTransactions {
+            account_include: vec![account.to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn filters(entries: &[(&str, &str)]) -> TransactionFilters {
+        entries
+            .iter()
+            .map(|(name, account)| (name.to_string(), filter(account)))
+            .collect()
+    }
+
+    fn accounts(owner: &str) -> AccountFilters {
+        let filter = SubscribeRequestFilterAccounts {
+            owner: vec![owner.to_string()],
+            ..Default::default()
+        };
+        HashMap::from([("owned".to_string(), filter)])
+    }
+
+    #[tokio::test]
+    async fn attach_primes_the_stream_with_current_filters() {
+        let handle = SubscriptionHandle::new(filters(&[("pump", "a")]), accounts("o"));
+        let mut stream = handle.attach().into_inner();
+
+        let request = stream.try_recv().unwrap();
+        assert_eq!(request.transactions, filters(&[("pump", "a")]));
+        assert_eq!(request.accounts, accounts("o"));
+        assert_eq!(request.ping, Some(SubscribeRequestPing { id: 1 }));
+        assert!(stream.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn duplicate_add_filter_is_rejected() {
+        let handle = SubscriptionHandle::new(filters(&[("pump", "a")]), HashMap::new());
+        let mut stream = handle.attach().into_inner();
+        stream.try_recv().unwrap();
+
+        assert!(handle.add_filter("pump", filter("b")).await.is_err());
+        assert_eq!(handle.filters(), filters(&[("pump", "a")]));
+        assert!(stream.try_recv().is_err());
+
+        handle.add_filter("raydium", filter("b")).await.unwrap();
+        let request = stream.try_recv().unwrap();
+        assert_eq!(request.transactions.len(), 2);
+        assert_eq!(request.ping, None);
+    }
+
+    #[tokio::test]
+    async fn replace_and_remove_filters() {
+        let handle = SubscriptionHandle::new(filters(&[("pump", "a")]), HashMap::new());
+        let mut stream = handle.attach().into_inner();
+        stream.try_recv().unwrap();
+
+        handle.replace_filter("pump", filter("b")).await;
+        assert_eq!(
+            stream.try_recv().unwrap().transactions,
+            filters(&[("pump", "b")])
+        );
+
+        assert!(handle.remove_filter("pump").await);
+        assert!(stream.try_recv().unwrap().transactions.is_empty());
+        assert!(!handle.remove_filter("pump").await);
+        assert!(stream.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn update_filters_broadcasts_once() {
+        let handle = SubscriptionHandle::new(
+            filters(&[("keep", "a"), ("drop", "b"), ("change", "c")]),
+            accounts("o"),
+        );
+        let mut stream = handle.attach().into_inner();
+        stream.try_recv().unwrap();
+
+        let wanted = filters(&[("keep", "a"), ("change", "d"), ("new", "e")]);
+        handle
+            .update_filters(wanted.clone(), accounts("p"))
+            .await
+            .unwrap();
+
+        let request = stream.try_recv().unwrap();
+        assert_eq!(request.transactions, wanted);
+        assert_eq!(request.accounts, accounts("p"));
+        assert!(stream.try_recv().is_err());
+        assert_eq!(handle.filters(), wanted);
+
+        handle.update_filters(wanted, accounts("p")).await.unwrap();
+        assert!(stream.try_recv().is_err());
+    }
+}
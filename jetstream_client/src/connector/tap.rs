@@ -0,0 +1,85 @@
+use std::marker::PhantomData;
+
+use jetstream_protos::jetstream::{
+    SubscribeParsedRequest, SubscribeRequest, SubscribeUpdate, SubscribeUpdateParsedTransaction,
+};
+use prost::bytes::{Buf, Bytes};
+use prost::Message;
+use tonic::codec::{Codec, DecodeBuf, Decoder, ProstCodec};
+use tonic::Status;
+
+/// gRPC method of the `Subscribe` stream
+pub(crate) const SUBSCRIBE_PATH: &str = "/jetstream.Jetstream/Subscribe";
+
+/// gRPC method of the `SubscribeParsed` stream
+pub(crate) const SUBSCRIBE_PARSED_PATH: &str = "/jetstream.Jetstream/SubscribeParsed";
+
+/// Codec of the `Subscribe` stream
+pub(crate) type SubscribeCodec = TapCodec<SubscribeRequest, SubscribeUpdate>;
+
+/// Codec of the `SubscribeParsed` stream
+pub(crate) type SubscribeParsedCodec =
+    TapCodec<SubscribeParsedRequest, SubscribeUpdateParsedTransaction>;
+
+/// Prost codec that also hands back the encoded bytes of each response
+/// when `keep_raw` is set, so they can be recorded unchanged
+#[derive(Debug)]
+pub(crate) struct TapCodec<Req, Resp> {
+    keep_raw: bool,
+    _messages: PhantomData<fn(Req) -> Resp>,
+}
+
+impl<Req, Resp> TapCodec<Req, Resp> {
+    pub fn new(keep_raw: bool) -> Self {
+        Self {
+            keep_raw,
+            _messages: PhantomData,
+        }
+    }
+}
+
+impl<Req, Resp> Codec for TapCodec<Req, Resp>
+where
+    Req: Message + Send + 'static,
+    Resp: Message + Default + Send + 'static,
+{
+    type Encode = Req;
+    type Decode = (Resp, Option<Bytes>);
+    type Encoder = <ProstCodec<Req, Resp> as Codec>::Encoder;
+    type Decoder = TapDecoder<Resp>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        ProstCodec::<Req, Resp>::default().encoder()
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        TapDecoder {
+            keep_raw: self.keep_raw,
+            _message: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TapDecoder<Resp> {
+    keep_raw: bool,
+    _message: PhantomData<fn() -> Resp>,
+}
+
+impl<Resp: Message + Default> Decoder for TapDecoder<Resp> {
+    type Item = (Resp, Option<Bytes>);
+    type Error = Status;
+
+    fn decode(&mut self, buf: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        let decode_error = |e: prost::DecodeError| Status::internal(e.to_string());
+        if !self.keep_raw {
+            return Resp::decode(buf)
+                .map(|message| Some((message, None)))
+                .map_err(decode_error);
+        }
+
+        let bytes = buf.copy_to_bytes(buf.remaining());
+        let message = Resp::decode(bytes.clone()).map_err(decode_error)?;
+        Ok(Some((message, Some(bytes))))
+    }
+}
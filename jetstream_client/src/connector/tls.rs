@@ -0,0 +1,60 @@
+use std::fs;
+
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
+
+use crate::client::ClientSettings;
+use crate::config::TlsConfig;
+
+/// Build the tonic TLS settings from the configured roots and client identity
+pub fn client_tls_config(config: &TlsConfig) -> anyhow::Result<ClientTlsConfig> {
+    let mut tls = ClientTlsConfig::new().assume_http2(true);
+    if !config.no_system_roots {
+        tls = tls.with_native_roots();
+    }
+
+    if let Some(ca_path) = &config.ca_cert {
+        let pem = fs::read(ca_path).map_err(|e| {
+            anyhow::anyhow!("Failed to read CA bundle {}: {}", ca_path.display(), e)
+        })?;
+        tls = tls.ca_certificate(Certificate::from_pem(pem));
+    }
+
+    if let (Some(cert_path), Some(key_path)) = (&config.client_cert, &config.client_key) {
+        let cert = fs::read(cert_path).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to read client certificate {}: {}",
+                cert_path.display(),
+                e
+            )
+        })?;
+        let key = fs::read(key_path).map_err(|e| {
+            anyhow::anyhow!("Failed to read client key {}: {}", key_path.display(), e)
+        })?;
+        tls = tls.identity(Identity::from_pem(cert, key));
+    }
+
+    if let Some(domain_name) = &config.domain_name {
+        tls = tls.domain_name(domain_name);
+    }
+
+    Ok(tls)
+}
+
+/// Connect to `url`, negotiating TLS for `https://` endpoints
+pub(crate) async fn connect(settings: &ClientSettings, url: &str) -> anyhow::Result<Channel> {
+    let mut endpoint = Channel::from_shared(url.to_string())?;
+    if let Some(connect_timeout) = settings.connect_timeout {
+        endpoint = endpoint.connect_timeout(connect_timeout);
+    }
+    if let Some(timeout) = settings.timeout {
+        endpoint = endpoint.timeout(timeout);
+    }
+
+    if url.starts_with("https://") {
+        endpoint = endpoint.tls_config(client_tls_config(&settings.tls)?)?;
+    } else if settings.tls.is_customized() {
+        log::warn!("TLS options are ignored for plaintext endpoint {}", url);
+    }
+
+    Ok(endpoint.connect().await?)
+}
@@ -0,0 +1,111 @@
+use jetstream_protos::jetstream::{CompiledInstruction, SubscribeUpdateTransactionInfo};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::message::v0::LoadedAddresses;
+use solana_sdk::pubkey::Pubkey;
+
+use super::lookup_tables::full_account_keys;
+
+/// Account keys of a transaction with the signer and writable flags from its header
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionAccounts {
+    keys: Vec<Pubkey>,
+    num_static: usize,
+    num_loaded_writable: usize,
+    num_required_signatures: usize,
+    num_readonly_signed: usize,
+    num_readonly_unsigned: usize,
+}
+
+impl TransactionAccounts {
+    /// Static account keys of `tx_info` followed by the `loaded` lookup table addresses
+    pub fn new(
+        tx_info: &SubscribeUpdateTransactionInfo,
+        loaded: LoadedAddresses,
+    ) -> anyhow::Result<Self> {
+        let num_loaded_writable = loaded.writable.len();
+        Ok(Self {
+            keys: full_account_keys(tx_info, loaded)?,
+            num_static: tx_info.account_keys.len(),
+            num_loaded_writable,
+            num_required_signatures: tx_info.num_required_signatures as usize,
+            num_readonly_signed: tx_info.num_readonly_signed_accounts as usize,
+            num_readonly_unsigned: tx_info.num_readonly_unsigned_accounts as usize,
+        })
+    }
+
+    pub fn keys(&self) -> &[Pubkey] {
+        &self.keys
+    }
+
+    /// Number of addresses loaded from lookup tables
+    pub fn num_loaded(&self) -> usize {
+        self.keys.len() - self.num_static
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Pubkey> {
+        self.keys.get(index)
+    }
+
+    pub fn is_signer(&self, index: usize) -> bool {
+        index < self.num_required_signatures
+    }
+
+    pub fn is_writable(&self, index: usize) -> bool {
+        if index >= self.num_static {
+            return index - self.num_static < self.num_loaded_writable;
+        }
+        if index < self.num_required_signatures {
+            index
+                < self
+                    .num_required_signatures
+                    .saturating_sub(self.num_readonly_signed)
+        } else {
+            index < self.num_static.saturating_sub(self.num_readonly_unsigned)
+        }
+    }
+
+    /// Program invoked by `instruction`, if its index resolves
+    pub fn program_id(&self, instruction: &CompiledInstruction) -> Option<&Pubkey> {
+        self.get(instruction.program_id_index as usize)
+    }
+
+    /// Account metas of `instruction` in instruction order
+    pub fn account_metas(
+        &self,
+        instruction: &CompiledInstruction,
+    ) -> anyhow::Result<Vec<AccountMeta>> {
+        instruction
+            .accounts
+            .iter()
+            .map(|index| {
+                let index = *index as usize;
+                let pubkey = self.get(index).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Account index {} out of range for {} account key(s)",
+                        index,
+                        self.keys.len()
+                    )
+                })?;
+                Ok(AccountMeta {
+                    pubkey: *pubkey,
+                    is_signer: self.is_signer(index),
+                    is_writable: self.is_writable(index),
+                })
+            })
+            .collect()
+    }
+}
+
+/// First `N` accounts of an instruction, for naming them
+pub(crate) fn keys_array<const N: usize>(accounts: &[Pubkey]) -> std::io::Result<[Pubkey; N]> {
+    accounts
+        .get(..N)
+        .and_then(|accounts| accounts.try_into().ok())
+        .ok_or_else(|| {
+            std::io::Error::other(format!(
+                "Expected at least {} accounts, got {}",
+                N,
+                accounts.len()
+            ))
+        })
+}
@@ -0,0 +1,712 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::str::FromStr;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use heck::ToSnakeCase;
+use serde_json::{json, Map, Value};
+use solana_sdk::hash::hashv;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+use super::reader::Reader;
+use super::registry::{DecodedInstruction, ProgramDecoder};
+
+/// Deepest nesting of defined types followed while decoding
+const MAX_TYPE_DEPTH: usize = 64;
+
+/// Instruction decoded with an Anchor IDL
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnchorInstruction {
+    pub program: String,
+    pub name: String,
+    pub args: Value,
+    /// IDL account names, aligned with the start of `accounts`
+    pub account_names: Vec<String>,
+    pub accounts: Vec<AccountMeta>,
+}
+
+impl AnchorInstruction {
+    /// `{"program", "instruction", "args", "accounts": {name: pubkey}}`; accounts past
+    /// the named ones are listed under `remaining_accounts`
+    pub fn to_json(&self) -> Value {
+        let mut named = Map::new();
+        for (name, meta) in self.account_names.iter().zip(&self.accounts) {
+            named.insert(name.clone(), Value::String(meta.pubkey.to_string()));
+        }
+        let remaining: Vec<Value> = self
+            .accounts
+            .iter()
+            .skip(self.account_names.len())
+            .map(|meta| Value::String(meta.pubkey.to_string()))
+            .collect();
+
+        let mut value = json!({
+            "program": self.program,
+            "instruction": self.name,
+            "args": self.args,
+            "accounts": named,
+        });
+        if !remaining.is_empty() {
+            value["remaining_accounts"] = Value::Array(remaining);
+        }
+        value
+    }
+}
+
+/// [`ProgramDecoder`] driven by an Anchor IDL; accepts both the legacy and the 0.30+ format
+#[derive(Clone, Debug)]
+pub struct AnchorIdlDecoder {
+    program_id: Pubkey,
+    name: String,
+    instructions: HashMap<[u8; 8], IdlInstruction>,
+    types: HashMap<String, IdlTypeDef>,
+}
+
+#[derive(Clone, Debug)]
+struct IdlInstruction {
+    name: String,
+    accounts: Vec<String>,
+    args: Vec<(String, IdlType)>,
+}
+
+#[derive(Clone, Debug)]
+enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    F32,
+    F64,
+    String,
+    Bytes,
+    Pubkey,
+    Vec(Box<IdlType>),
+    Option(Box<IdlType>),
+    COption(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined(String),
+}
+
+#[derive(Clone, Debug)]
+enum IdlFields {
+    Named(Vec<(String, IdlType)>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Clone, Debug)]
+enum IdlTypeDef {
+    Struct(IdlFields),
+    Enum(Vec<(String, Option<IdlFields>)>),
+    Alias(IdlType),
+}
+
+impl AnchorIdlDecoder {
+    /// Load an IDL JSON file; `program_id` overrides the address in the IDL
+    pub fn from_file(path: &Path, program_id: Option<Pubkey>) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open IDL {}: {}", path.display(), e))?;
+        let idl: Value = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| anyhow::anyhow!("Failed to parse IDL {}: {}", path.display(), e))?;
+        Self::from_idl(&idl, program_id)
+            .map_err(|e| e.context(format!("Invalid IDL {}", path.display())))
+    }
+
+    /// Build a decoder from a parsed IDL; `program_id` overrides the address in the IDL
+    pub fn from_idl(idl: &Value, program_id: Option<Pubkey>) -> anyhow::Result<Self> {
+        let program_id = match program_id {
+            Some(program_id) => program_id,
+            None => {
+                let address = idl["address"]
+                    .as_str()
+                    .or_else(|| idl["metadata"]["address"].as_str())
+                    .ok_or_else(|| anyhow::anyhow!("IDL has no program address"))?;
+                Pubkey::from_str(address)
+                    .map_err(|e| anyhow::anyhow!("Invalid IDL address {}: {}", address, e))?
+            }
+        };
+        let name = idl["metadata"]["name"]
+            .as_str()
+            .or_else(|| idl["name"].as_str())
+            .unwrap_or("anchor")
+            .to_string();
+
+        let mut types = HashMap::new();
+        // Legacy IDLs define account layouts next to the types
+        for def in array(&idl["accounts"]).iter().chain(array(&idl["types"])) {
+            if def.get("type").is_none() {
+                continue;
+            }
+            let type_name = str_field(def, "name")?;
+            types.insert(type_name.to_string(), parse_type_def(&def["type"])?);
+        }
+
+        let mut instructions = HashMap::new();
+        for ix in array(&idl["instructions"]) {
+            let ix_name = str_field(ix, "name")?;
+            let discriminator = match ix.get("discriminator") {
+                Some(discriminator) => serde_json::from_value::<[u8; 8]>(discriminator.clone())
+                    .map_err(|e| {
+                        anyhow::anyhow!("Invalid discriminator of instruction {}: {}", ix_name, e)
+                    })?,
+                None => instruction_discriminator(ix_name),
+            };
+
+            let mut accounts = Vec::new();
+            flatten_accounts(array(&ix["accounts"]), "", &mut accounts);
+            let args = array(&ix["args"])
+                .iter()
+                .map(|arg| {
+                    Ok((
+                        str_field(arg, "name")?.to_string(),
+                        parse_type(&arg["type"])?,
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()
+                .map_err(|e: anyhow::Error| {
+                    e.context(format!("Invalid args of instruction {}", ix_name))
+                })?;
+
+            instructions.insert(
+                discriminator,
+                IdlInstruction {
+                    name: ix_name.to_string(),
+                    accounts,
+                    args,
+                },
+            );
+        }
+
+        Ok(Self {
+            program_id,
+            name,
+            instructions,
+            types,
+        })
+    }
+
+    /// Decode instruction data invoked with `accounts`
+    pub fn decode_instruction(
+        &self,
+        data: &[u8],
+        accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<AnchorInstruction> {
+        let discriminator: [u8; 8] = data
+            .get(..8)
+            .and_then(|discriminator| discriminator.try_into().ok())
+            .ok_or_else(|| anyhow::anyhow!("Instruction data shorter than a discriminator"))?;
+        let ix = self.instructions.get(&discriminator).ok_or_else(|| {
+            anyhow::anyhow!("{} discriminator {:?} not found", self.name, discriminator)
+        })?;
+
+        let mut reader = Reader::new(&data[8..]);
+        let mut args = Map::new();
+        for (name, ty) in &ix.args {
+            let value = self.decode_value(ty, &mut reader, 0).map_err(|e| {
+                anyhow::anyhow!("Failed to decode arg {} of {}: {}", name, ix.name, e)
+            })?;
+            args.insert(name.clone(), value);
+        }
+
+        Ok(AnchorInstruction {
+            program: self.name.clone(),
+            name: ix.name.clone(),
+            args: Value::Object(args),
+            account_names: ix.accounts.clone(),
+            accounts,
+        })
+    }
+
+    fn decode_value(
+        &self,
+        ty: &IdlType,
+        reader: &mut Reader,
+        depth: usize,
+    ) -> std::io::Result<Value> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err(std::io::Error::other("IDL types nested too deeply"));
+        }
+        let value = match ty {
+            IdlType::Bool => Value::Bool(reader.u8()? != 0),
+            IdlType::U8 => json!(reader.u8()?),
+            IdlType::I8 => json!(reader.u8()? as i8),
+            IdlType::U16 => json!(reader.u16()?),
+            IdlType::I16 => json!(reader.u16()? as i16),
+            IdlType::U32 => json!(reader.u32()?),
+            IdlType::I32 => json!(reader.u32()? as i32),
+            IdlType::U64 => json!(reader.u64()?),
+            IdlType::I64 => json!(reader.u64()? as i64),
+            // JSON numbers cannot hold 128-bit integers exactly
+            IdlType::U128 => Value::String(reader.u128()?.to_string()),
+            IdlType::I128 => Value::String((reader.u128()? as i128).to_string()),
+            IdlType::F32 => json!(f32::from_bits(reader.u32()?)),
+            IdlType::F64 => json!(f64::from_bits(reader.u64()?)),
+            IdlType::String => {
+                let len = reader.u32()? as usize;
+                Value::String(
+                    String::from_utf8(reader.bytes(len)?.to_vec())
+                        .map_err(std::io::Error::other)?,
+                )
+            }
+            IdlType::Bytes => {
+                let len = reader.u32()? as usize;
+                Value::String(BASE64_STANDARD.encode(reader.bytes(len)?))
+            }
+            IdlType::Pubkey => Value::String(reader.pubkey()?.to_string()),
+            IdlType::Vec(inner) => {
+                let len = reader.u32()? as usize;
+                // Elements take at least a byte each, so a longer length is corrupt
+                if len > reader.rest().len() {
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                }
+                self.decode_sequence(inner, len, reader, depth)?
+            }
+            IdlType::Array(inner, len) => self.decode_sequence(inner, *len, reader, depth)?,
+            IdlType::Option(inner) => match reader.u8()? {
+                0 => Value::Null,
+                _ => self.decode_value(inner, reader, depth + 1)?,
+            },
+            IdlType::COption(inner) => match reader.u32()? {
+                0 => Value::Null,
+                _ => self.decode_value(inner, reader, depth + 1)?,
+            },
+            IdlType::Defined(name) => {
+                let def = self.types.get(name).ok_or_else(|| {
+                    std::io::Error::other(format!("Type {} is not defined in the IDL", name))
+                })?;
+                self.decode_defined(def, reader, depth + 1)?
+            }
+        };
+        Ok(value)
+    }
+
+    fn decode_sequence(
+        &self,
+        ty: &IdlType,
+        len: usize,
+        reader: &mut Reader,
+        depth: usize,
+    ) -> std::io::Result<Value> {
+        (0..len)
+            .map(|_| self.decode_value(ty, reader, depth + 1))
+            .collect::<std::io::Result<_>>()
+            .map(Value::Array)
+    }
+
+    fn decode_defined(
+        &self,
+        def: &IdlTypeDef,
+        reader: &mut Reader,
+        depth: usize,
+    ) -> std::io::Result<Value> {
+        match def {
+            IdlTypeDef::Struct(fields) => self.decode_fields(fields, reader, depth),
+            IdlTypeDef::Enum(variants) => {
+                let index = reader.u8()? as usize;
+                let (name, fields) = variants.get(index).ok_or_else(|| {
+                    std::io::Error::other(format!("Enum variant {} out of range", index))
+                })?;
+                match fields {
+                    None => Ok(Value::String(name.clone())),
+                    Some(fields) => {
+                        let mut value = Map::new();
+                        value.insert(name.clone(), self.decode_fields(fields, reader, depth)?);
+                        Ok(Value::Object(value))
+                    }
+                }
+            }
+            IdlTypeDef::Alias(ty) => self.decode_value(ty, reader, depth),
+        }
+    }
+
+    fn decode_fields(
+        &self,
+        fields: &IdlFields,
+        reader: &mut Reader,
+        depth: usize,
+    ) -> std::io::Result<Value> {
+        match fields {
+            IdlFields::Named(fields) => {
+                let mut value = Map::new();
+                for (name, ty) in fields {
+                    value.insert(name.clone(), self.decode_value(ty, reader, depth)?);
+                }
+                Ok(Value::Object(value))
+            }
+            IdlFields::Tuple(types) => types
+                .iter()
+                .map(|ty| self.decode_value(ty, reader, depth))
+                .collect::<std::io::Result<_>>()
+                .map(Value::Array),
+        }
+    }
+}
+
+impl ProgramDecoder for AnchorIdlDecoder {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decode(
+        &self,
+        data: &[u8],
+        accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<DecodedInstruction> {
+        Ok(DecodedInstruction::Anchor(Box::new(
+            self.decode_instruction(data, accounts)?,
+        )))
+    }
+}
+
+/// Anchor instruction discriminator: the first 8 bytes of sha256("global:<snake_case name>").
+/// Legacy IDLs use camelCase names, converted with heck as Anchor does.
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let hash = hashv(&[b"global:", name.to_snake_case().as_bytes()]);
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+fn array(value: &Value) -> &[Value] {
+    value.as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn str_field<'a>(value: &'a Value, field: &str) -> anyhow::Result<&'a str> {
+    value[field]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing {} in {}", field, value))
+}
+
+/// Account names in instruction order; nested account groups become `group.account`
+fn flatten_accounts(accounts: &[Value], prefix: &str, names: &mut Vec<String>) {
+    for account in accounts {
+        let name = format!(
+            "{}{}",
+            prefix,
+            account["name"].as_str().unwrap_or("unknown")
+        );
+        match account["accounts"].as_array() {
+            Some(nested) => flatten_accounts(nested, &format!("{}.", name), names),
+            None => names.push(name),
+        }
+    }
+}
+
+fn parse_type(value: &Value) -> anyhow::Result<IdlType> {
+    if let Some(name) = value.as_str() {
+        return Ok(match name {
+            "bool" => IdlType::Bool,
+            "u8" => IdlType::U8,
+            "i8" => IdlType::I8,
+            "u16" => IdlType::U16,
+            "i16" => IdlType::I16,
+            "u32" => IdlType::U32,
+            "i32" => IdlType::I32,
+            "u64" => IdlType::U64,
+            "i64" => IdlType::I64,
+            "u128" => IdlType::U128,
+            "i128" => IdlType::I128,
+            "f32" => IdlType::F32,
+            "f64" => IdlType::F64,
+            "string" => IdlType::String,
+            "bytes" => IdlType::Bytes,
+            "publicKey" | "pubkey" => IdlType::Pubkey,
+            _ => anyhow::bail!("Unsupported IDL type {}", name),
+        });
+    }
+
+    if let Some(inner) = value.get("vec") {
+        Ok(IdlType::Vec(Box::new(parse_type(inner)?)))
+    } else if let Some(inner) = value.get("option") {
+        Ok(IdlType::Option(Box::new(parse_type(inner)?)))
+    } else if let Some(inner) = value.get("coption") {
+        Ok(IdlType::COption(Box::new(parse_type(inner)?)))
+    } else if let Some([inner, len]) = value
+        .get("array")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        let len = len
+            .as_u64()
+            .ok_or_else(|| anyhow::anyhow!("Unsupported array length {}", len))?;
+        Ok(IdlType::Array(Box::new(parse_type(inner)?), len as usize))
+    } else if let Some(defined) = value.get("defined") {
+        let name = defined
+            .as_str()
+            .or_else(|| defined["name"].as_str())
+            .ok_or_else(|| anyhow::anyhow!("Invalid defined type {}", defined))?;
+        Ok(IdlType::Defined(name.to_string()))
+    } else {
+        anyhow::bail!("Unsupported IDL type {}", value)
+    }
+}
+
+fn parse_fields(fields: &[Value]) -> anyhow::Result<IdlFields> {
+    if fields.iter().all(|field| field.get("name").is_some()) {
+        fields
+            .iter()
+            .map(|field| {
+                Ok((
+                    str_field(field, "name")?.to_string(),
+                    parse_type(&field["type"])?,
+                ))
+            })
+            .collect::<anyhow::Result<_>>()
+            .map(IdlFields::Named)
+    } else {
+        fields
+            .iter()
+            .map(parse_type)
+            .collect::<anyhow::Result<_>>()
+            .map(IdlFields::Tuple)
+    }
+}
+
+fn parse_type_def(value: &Value) -> anyhow::Result<IdlTypeDef> {
+    match value["kind"].as_str() {
+        Some("struct") => Ok(IdlTypeDef::Struct(parse_fields(array(&value["fields"]))?)),
+        Some("enum") => array(&value["variants"])
+            .iter()
+            .map(|variant| {
+                let fields = variant["fields"]
+                    .as_array()
+                    .map(|fields| parse_fields(fields))
+                    .transpose()?;
+                Ok((str_field(variant, "name")?.to_string(), fields))
+            })
+            .collect::<anyhow::Result<_>>()
+            .map(IdlTypeDef::Enum),
+        Some("type") | Some("alias") => Ok(IdlTypeDef::Alias(parse_type(&value["alias"])?)),
+        _ => anyhow::bail!("Unsupported IDL type definition {}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::super::pumpfun;
+    use super::*;
+
+    fn load(name: &str) -> AnchorIdlDecoder {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/idls")
+            .join(name);
+        AnchorIdlDecoder::from_file(&path, None).unwrap()
+    }
+
+    fn data(discriminator: [u8; 8], fields: &[&[u8]]) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        for field in fields {
+            data.extend_from_slice(field);
+        }
+        data
+    }
+
+    fn metas(len: usize) -> Vec<AccountMeta> {
+        (0..len)
+            .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+            .collect()
+    }
+
+    #[test]
+    fn discriminators_match_anchor() {
+        for (name, discriminator) in [
+            ("buy", pumpfun::BUY_IX_DISCM),
+            ("setParams", pumpfun::SET_PARAMS_IX_DISCM),
+            ("extendAccount", pumpfun::EXTEND_ACCOUNT_IX_DISCM),
+            ("collectCreatorFee", pumpfun::COLLECT_CREATOR_FEE_IX_DISCM),
+            ("swap", [248, 198, 158, 145, 225, 117, 135, 200]),
+            ("buyExactSOLIn", [56, 252, 116, 8, 158, 223, 205, 95]),
+            ("buy_exact_sol_in", [56, 252, 116, 8, 158, 223, 205, 95]),
+        ] {
+            assert_eq!(instruction_discriminator(name), discriminator, "{}", name);
+        }
+    }
+
+    #[test]
+    fn decodes_legacy_idl() {
+        let decoder = load("legacy.json");
+        assert_eq!(decoder.name(), "order_book");
+        assert_eq!(decoder.program_id(), pumpfun::PUMPFUN_PROGRAM_ID);
+
+        let (mint_a, mint_b, referrer) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let ix_data = data(
+            instruction_discriminator("place_order"),
+            &[
+                &[1],
+                &1_250u64.to_le_bytes(),
+                &2u32.to_le_bytes(),
+                mint_a.as_ref(),
+                &7u16.to_le_bytes(),
+                mint_b.as_ref(),
+                &3u16.to_le_bytes(),
+                &[1],
+                &(-60i64).to_le_bytes(),
+                &1u32.to_le_bytes(),
+                referrer.as_ref(),
+            ],
+        );
+        let accounts = metas(4);
+        let ix = decoder
+            .decode_instruction(&ix_data, accounts.clone())
+            .unwrap();
+        assert_eq!(ix.name, "placeOrder");
+        assert_eq!(
+            ix.args,
+            json!({
+                "order": {
+                    "side": "Ask",
+                    "price": 1_250,
+                    "legs": [
+                        {"mint": mint_a.to_string(), "weight": 7},
+                        {"mint": mint_b.to_string(), "weight": 3},
+                    ],
+                },
+                "expiry": -60,
+                "referrer": referrer.to_string(),
+            })
+        );
+        assert_eq!(
+            ix.to_json()["accounts"],
+            json!({
+                "market": accounts[0].pubkey.to_string(),
+                "authority.owner": accounts[1].pubkey.to_string(),
+                "authority.delegate": accounts[2].pubkey.to_string(),
+            })
+        );
+        assert_eq!(
+            ix.to_json()["remaining_accounts"],
+            json!([accounts[3].pubkey.to_string()])
+        );
+    }
+
+    #[test]
+    fn legacy_options_can_be_empty() {
+        let decoder = load("legacy.json");
+        let ix_data = data(
+            instruction_discriminator("placeOrder"),
+            &[
+                &[0],
+                &5u64.to_le_bytes(),
+                &0u32.to_le_bytes(),
+                &[0],
+                &0u32.to_le_bytes(),
+            ],
+        );
+        let ix = decoder.decode_instruction(&ix_data, metas(3)).unwrap();
+        assert_eq!(
+            ix.args,
+            json!({
+                "order": {"side": "Bid", "price": 5, "legs": []},
+                "expiry": null,
+                "referrer": null,
+            })
+        );
+
+        let ix_data = data(
+            instruction_discriminator("buyExactSOLIn"),
+            &[&1_000u64.to_le_bytes()],
+        );
+        let ix = decoder.decode_instruction(&ix_data, metas(1)).unwrap();
+        assert_eq!(ix.name, "buyExactSOLIn");
+        assert_eq!(ix.args, json!({"lamports": 1_000}));
+    }
+
+    #[test]
+    fn decodes_v030_idl() {
+        let decoder = load("v030.json");
+        assert_eq!(decoder.name(), "router");
+
+        let via = Pubkey::new_unique();
+        let ix_data = data(
+            [248, 198, 158, 145, 225, 117, 135, 200],
+            &[
+                &500u64.to_le_bytes(),
+                &[1],
+                via.as_ref(),
+                &30u16.to_le_bytes(),
+                &[1],
+                &u128::MAX.to_le_bytes(),
+                &2u32.to_le_bytes(),
+                b"hi",
+            ],
+        );
+        let accounts = metas(2);
+        let ix = decoder
+            .decode_instruction(&ix_data, accounts.clone())
+            .unwrap();
+        assert_eq!(
+            ix.to_json(),
+            json!({
+                "program": "router",
+                "instruction": "swap",
+                "args": {
+                    "params": {
+                        "amount_in": 500,
+                        "route": {"Hop": {"via": via.to_string(), "fee_bps": 30}},
+                        "limit": u128::MAX.to_string(),
+                    },
+                    "memo": "hi",
+                },
+                "accounts": {
+                    "pool": accounts[0].pubkey.to_string(),
+                    "user": accounts[1].pubkey.to_string(),
+                },
+            })
+        );
+
+        let ix_data = data(
+            [248, 198, 158, 145, 225, 117, 135, 200],
+            &[
+                &1u64.to_le_bytes(),
+                &[2, 9, 4, 5],
+                &[0],
+                &0u32.to_le_bytes(),
+            ],
+        );
+        let ix = decoder.decode_instruction(&ix_data, metas(2)).unwrap();
+        assert_eq!(ix.args["params"]["route"], json!({"Split": [9, [4, 5]]}));
+        assert_eq!(ix.args["params"]["limit"], Value::Null);
+    }
+
+    #[test]
+    fn rejects_bad_instruction_data() {
+        let decoder = load("v030.json");
+        assert!(decoder.decode_instruction(&[1, 2, 3], Vec::new()).is_err());
+        assert!(decoder.decode_instruction(&[0; 16], Vec::new()).is_err());
+
+        let bad_variant = data(
+            [248, 198, 158, 145, 225, 117, 135, 200],
+            &[&1u64.to_le_bytes(), &[3]],
+        );
+        let err = decoder
+            .decode_instruction(&bad_variant, Vec::new())
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("variant 3 out of range"),
+            "{}",
+            err
+        );
+
+        let truncated = data([248, 198, 158, 145, 225, 117, 135, 200], &[&[1, 0]]);
+        assert!(decoder.decode_instruction(&truncated, Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt;
+
+use jetstream_protos::jetstream::{MessageAddressTableLookup, SubscribeUpdateTransactionInfo};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+const PUBKEY_BYTES: usize = 32;
+const SIGNATURE_BYTES: usize = 64;
+const HASH_BYTES: usize = 32;
+
+/// Proto `bytes` field a conversion failed on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BytesField {
+    Pubkey,
+    /// Static account key at this index
+    AccountKey(usize),
+    /// Lookup table key of the address table lookup at this index
+    LookupTableKey(usize),
+    /// Signature at this index
+    Signature(usize),
+    RecentBlockhash,
+}
+
+impl BytesField {
+    /// Length the field must have
+    pub fn expected_len(&self) -> usize {
+        match self {
+            Self::Pubkey | Self::AccountKey(_) | Self::LookupTableKey(_) => PUBKEY_BYTES,
+            Self::Signature(_) => SIGNATURE_BYTES,
+            Self::RecentBlockhash => HASH_BYTES,
+        }
+    }
+}
+
+impl fmt::Display for BytesField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pubkey => write!(f, "pubkey"),
+            Self::AccountKey(index) => write!(f, "account key #{}", index),
+            Self::LookupTableKey(index) => write!(f, "lookup table key #{}", index),
+            Self::Signature(index) => write!(f, "signature #{}", index),
+            Self::RecentBlockhash => write!(f, "recent blockhash"),
+        }
+    }
+}
+
+/// A proto `bytes` field did not have the length of the type it encodes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BytesError {
+    pub field: BytesField,
+    pub len: usize,
+}
+
+impl fmt::Display for BytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid {} of {} bytes, expected {}",
+            self.field,
+            self.len,
+            self.field.expected_len()
+        )
+    }
+}
+
+impl std::error::Error for BytesError {}
+
+fn array<const N: usize>(bytes: &[u8], field: BytesField) -> Result<[u8; N], BytesError> {
+    bytes.try_into().map_err(|_| BytesError {
+        field,
+        len: bytes.len(),
+    })
+}
+
+/// Convert a 32 byte pubkey
+pub fn pubkey(bytes: &[u8]) -> Result<Pubkey, BytesError> {
+    array(bytes, BytesField::Pubkey).map(Pubkey::new_from_array)
+}
+
+/// Static account keys of a transaction, with room reserved for `additional`
+/// keys so loaded addresses can be appended without reallocating
+pub fn account_keys(
+    tx_info: &SubscribeUpdateTransactionInfo,
+    additional: usize,
+) -> Result<Vec<Pubkey>, BytesError> {
+    let mut keys = Vec::with_capacity(tx_info.account_keys.len() + additional);
+    for (index, key) in tx_info.account_keys.iter().enumerate() {
+        keys.push(Pubkey::new_from_array(array(
+            key,
+            BytesField::AccountKey(index),
+        )?));
+    }
+    Ok(keys)
+}
+
+/// Key of the lookup table at `index` of the transaction's address table lookups
+pub fn lookup_table_key(
+    index: usize,
+    lookup: &MessageAddressTableLookup,
+) -> Result<Pubkey, BytesError> {
+    array(&lookup.account_key, BytesField::LookupTableKey(index)).map(Pubkey::new_from_array)
+}
+
+/// All signatures of a transaction, falling back to the first one alone
+pub fn signatures(tx_info: &SubscribeUpdateTransactionInfo) -> Result<Vec<Signature>, BytesError> {
+    let signatures = if tx_info.signatures.is_empty() {
+        std::slice::from_ref(&tx_info.signature)
+    } else {
+        tx_info.signatures.as_slice()
+    };
+    signatures
+        .iter()
+        .enumerate()
+        .map(|(index, signature)| {
+            array::<SIGNATURE_BYTES>(signature, BytesField::Signature(index)).map(Signature::from)
+        })
+        .collect()
+}
+
+/// Blockhash the transaction was signed against
+pub fn recent_blockhash(tx_info: &SubscribeUpdateTransactionInfo) -> Result<Hash, BytesError> {
+    array(&tx_info.recent_blockhash, BytesField::RecentBlockhash).map(Hash::new_from_array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx_info() -> SubscribeUpdateTransactionInfo {
+        SubscribeUpdateTransactionInfo {
+            signature: vec![1; SIGNATURE_BYTES],
+            recent_blockhash: vec![2; HASH_BYTES],
+            signatures: vec![vec![1; SIGNATURE_BYTES], vec![3; SIGNATURE_BYTES]],
+            account_keys: vec![vec![4; PUBKEY_BYTES], vec![5; PUBKEY_BYTES]],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn converts_well_formed_fields() {
+        let tx_info = tx_info();
+        let keys = account_keys(&tx_info, 3).unwrap();
+        assert_eq!(
+            keys,
+            [
+                Pubkey::new_from_array([4; 32]),
+                Pubkey::new_from_array([5; 32])
+            ]
+        );
+        assert!(keys.capacity() >= 5);
+        assert_eq!(
+            signatures(&tx_info).unwrap(),
+            [Signature::from([1; 64]), Signature::from([3; 64])]
+        );
+        assert_eq!(
+            recent_blockhash(&tx_info).unwrap(),
+            Hash::new_from_array([2; 32])
+        );
+        assert_eq!(pubkey(&[6; 32]).unwrap(), Pubkey::new_from_array([6; 32]));
+    }
+
+    #[test]
+    fn pubkeys_of_wrong_length_are_rejected() {
+        for len in [0, 31, 33] {
+            assert_eq!(
+                pubkey(&vec![0; len]),
+                Err(BytesError {
+                    field: BytesField::Pubkey,
+                    len,
+                })
+            );
+        }
+
+        let mut short = tx_info();
+        short.account_keys[1].pop();
+        assert_eq!(
+            account_keys(&short, 0),
+            Err(BytesError {
+                field: BytesField::AccountKey(1),
+                len: 31,
+            })
+        );
+
+        let mut long = tx_info();
+        long.account_keys[0].push(0);
+        let err = account_keys(&long, 0).unwrap_err();
+        assert_eq!(err.field, BytesField::AccountKey(0));
+        assert_eq!(
+            err.to_string(),
+            "Invalid account key #0 of 33 bytes, expected 32"
+        );
+
+        let lookup = MessageAddressTableLookup {
+            account_key: vec![0; 20],
+            ..Default::default()
+        };
+        assert_eq!(
+            lookup_table_key(2, &lookup),
+            Err(BytesError {
+                field: BytesField::LookupTableKey(2),
+                len: 20,
+            })
+        );
+    }
+
+    #[test]
+    fn signatures_of_wrong_length_are_rejected() {
+        let mut short = tx_info();
+        short.signatures[1].truncate(63);
+        assert_eq!(
+            signatures(&short),
+            Err(BytesError {
+                field: BytesField::Signature(1),
+                len: 63,
+            })
+        );
+
+        let mut long = tx_info();
+        long.signatures[0].push(0);
+        assert_eq!(
+            signatures(&long).unwrap_err().to_string(),
+            "Invalid signature #0 of 65 bytes, expected 64"
+        );
+    }
+
+    #[test]
+    fn missing_signature_list_falls_back_to_first_signature() {
+        let mut tx_info = tx_info();
+        tx_info.signatures.clear();
+        assert_eq!(signatures(&tx_info).unwrap(), [Signature::from([1; 64])]);
+
+        tx_info.signature.clear();
+        assert_eq!(
+            signatures(&tx_info),
+            Err(BytesError {
+                field: BytesField::Signature(0),
+                len: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn blockhashes_of_wrong_length_are_rejected() {
+        for len in [0, 31, 64] {
+            let mut tx_info = tx_info();
+            tx_info.recent_blockhash = vec![0; len];
+            assert_eq!(
+                recent_blockhash(&tx_info),
+                Err(BytesError {
+                    field: BytesField::RecentBlockhash,
+                    len,
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use jetstream_protos::jetstream::SubscribeUpdateTransactionInfo;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use super::reader::Reader;
+use super::registry::{DecodedInstruction, ProgramDecoder};
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// Compute units granted to each instruction when no limit is requested
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+/// Most compute units a transaction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub enum ComputeBudgetIx {
+    /// Deprecated combined units and fee request
+    RequestUnits {
+        units: u32,
+        additional_fee: u32,
+    },
+    RequestHeapFrame {
+        bytes: u32,
+    },
+    SetComputeUnitLimit {
+        units: u32,
+    },
+    /// Price per compute unit in micro-lamports
+    SetComputeUnitPrice {
+        micro_lamports: u64,
+    },
+    SetLoadedAccountsDataSizeLimit {
+        bytes: u32,
+    },
+}
+
+impl ComputeBudgetIx {
+    /// Decode compute budget instruction data: a one byte tag followed by the args
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = Reader::new(buf);
+        let tag = reader.u8()?;
+        let ix = match tag {
+            0 => Self::RequestUnits {
+                units: reader.u32()?,
+                additional_fee: reader.u32()?,
+            },
+            1 => Self::RequestHeapFrame {
+                bytes: reader.u32()?,
+            },
+            2 => Self::SetComputeUnitLimit {
+                units: reader.u32()?,
+            },
+            3 => Self::SetComputeUnitPrice {
+                micro_lamports: reader.u64()?,
+            },
+            4 => Self::SetLoadedAccountsDataSizeLimit {
+                bytes: reader.u32()?,
+            },
+            _ => {
+                return Err(std::io::Error::other(format!(
+                    "Compute budget instruction tag {} not supported",
+                    tag
+                )))
+            }
+        };
+        Ok(ix)
+    }
+}
+
+/// Compute budget requested by a transaction
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub unit_limit: Option<u32>,
+    pub unit_price_micro_lamports: Option<u64>,
+    pub heap_frame_bytes: Option<u32>,
+    pub loaded_accounts_data_size_limit: Option<u32>,
+    /// Top-level instructions other than compute budget ones
+    pub num_other_instructions: usize,
+}
+
+impl ComputeBudget {
+    /// Collect the compute budget instructions of a transaction.
+    ///
+    /// Program ids cannot be loaded from lookup tables, so the static account
+    /// keys are enough to find them.
+    pub fn from_transaction(tx_info: &SubscribeUpdateTransactionInfo) -> anyhow::Result<Self> {
+        let mut budget = Self::default();
+        for (index, instruction) in tx_info.instructions.iter().enumerate() {
+            let is_compute_budget = tx_info
+                .account_keys
+                .get(instruction.program_id_index as usize)
+                .is_some_and(|key| key.as_slice() == COMPUTE_BUDGET_PROGRAM_ID.as_ref());
+            if !is_compute_budget {
+                budget.num_other_instructions += 1;
+                continue;
+            }
+
+            let ix = ComputeBudgetIx::deserialize(&instruction.data).map_err(|e| {
+                anyhow::anyhow!("Invalid compute budget instruction #{}: {}", index, e)
+            })?;
+            budget.apply(ix);
+        }
+        Ok(budget)
+    }
+
+    fn apply(&mut self, ix: ComputeBudgetIx) {
+        match ix {
+            ComputeBudgetIx::RequestUnits { units, .. }
+            | ComputeBudgetIx::SetComputeUnitLimit { units } => self.unit_limit = Some(units),
+            ComputeBudgetIx::RequestHeapFrame { bytes } => self.heap_frame_bytes = Some(bytes),
+            ComputeBudgetIx::SetComputeUnitPrice { micro_lamports } => {
+                self.unit_price_micro_lamports = Some(micro_lamports)
+            }
+            ComputeBudgetIx::SetLoadedAccountsDataSizeLimit { bytes } => {
+                self.loaded_accounts_data_size_limit = Some(bytes)
+            }
+        }
+    }
+
+    /// Requested compute unit limit, or the default for the transaction's instructions
+    pub fn effective_unit_limit(&self) -> u32 {
+        self.unit_limit
+            .unwrap_or_else(|| {
+                DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
+                    .saturating_mul(self.num_other_instructions as u32)
+            })
+            .min(MAX_COMPUTE_UNIT_LIMIT)
+    }
+
+    /// Priority fee in lamports: unit price times unit limit, rounded up
+    pub fn priority_fee_lamports(&self) -> u64 {
+        let price = self.unit_price_micro_lamports.unwrap_or(0) as u128;
+        let micro_lamports = price * self.effective_unit_limit() as u128;
+        micro_lamports
+            .div_ceil(MICRO_LAMPORTS_PER_LAMPORT)
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+}
+
+/// Priority fee in lamports paid by a transaction
+pub fn priority_fee_lamports(tx_info: &SubscribeUpdateTransactionInfo) -> anyhow::Result<u64> {
+    Ok(ComputeBudget::from_transaction(tx_info)?.priority_fee_lamports())
+}
+
+/// [`ProgramDecoder`] for the compute budget program
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ComputeBudgetDecoder;
+
+impl ProgramDecoder for ComputeBudgetDecoder {
+    fn program_id(&self) -> Pubkey {
+        COMPUTE_BUDGET_PROGRAM_ID
+    }
+
+    fn name(&self) -> &str {
+        "compute-budget"
+    }
+
+    fn decode(
+        &self,
+        data: &[u8],
+        _accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<DecodedInstruction> {
+        Ok(DecodedInstruction::ComputeBudget(
+            ComputeBudgetIx::deserialize(data)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jetstream_protos::jetstream::CompiledInstruction;
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    use super::*;
+
+    /// Transaction whose instructions invoke the compute budget program
+    /// (account 1) with `budget` data, followed by `other` instructions to account 2
+    fn transaction(budget: &[Vec<u8>], other: usize) -> SubscribeUpdateTransactionInfo {
+        let compute_budget = |data: &Vec<u8>| CompiledInstruction {
+            program_id_index: 1,
+            accounts: Vec::new(),
+            data: data.clone(),
+        };
+        let instructions = budget
+            .iter()
+            .map(compute_budget)
+            .chain((0..other).map(|_| CompiledInstruction {
+                program_id_index: 2,
+                accounts: vec![0],
+                data: vec![2, 0, 0, 0],
+            }))
+            .collect();
+        SubscribeUpdateTransactionInfo {
+            account_keys: vec![
+                Pubkey::new_unique().to_bytes().to_vec(),
+                COMPUTE_BUDGET_PROGRAM_ID.to_bytes().to_vec(),
+                Pubkey::default().to_bytes().to_vec(),
+            ],
+            instructions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decodes_each_instruction() {
+        let cases = [
+            (
+                ComputeBudgetInstruction::request_heap_frame(256 * 1024),
+                ComputeBudgetIx::RequestHeapFrame { bytes: 256 * 1024 },
+            ),
+            (
+                ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+                ComputeBudgetIx::SetComputeUnitLimit { units: 300_000 },
+            ),
+            (
+                ComputeBudgetInstruction::set_compute_unit_price(12_345),
+                ComputeBudgetIx::SetComputeUnitPrice {
+                    micro_lamports: 12_345,
+                },
+            ),
+            (
+                ComputeBudgetInstruction::set_loaded_accounts_data_size_limit(64 * 1024),
+                ComputeBudgetIx::SetLoadedAccountsDataSizeLimit { bytes: 64 * 1024 },
+            ),
+        ];
+        for (instruction, expected) in cases {
+            assert_eq!(instruction.program_id, COMPUTE_BUDGET_PROGRAM_ID);
+            assert_eq!(
+                ComputeBudgetIx::deserialize(&instruction.data).unwrap(),
+                expected
+            );
+        }
+
+        let mut request_units = vec![0];
+        request_units.extend_from_slice(&500_000u32.to_le_bytes());
+        request_units.extend_from_slice(&10_000u32.to_le_bytes());
+        assert_eq!(
+            ComputeBudgetIx::deserialize(&request_units).unwrap(),
+            ComputeBudgetIx::RequestUnits {
+                units: 500_000,
+                additional_fee: 10_000,
+            }
+        );
+        assert!(ComputeBudgetIx::deserialize(&[5]).is_err());
+        assert!(ComputeBudgetIx::deserialize(&[2, 1]).is_err());
+    }
+
+    #[test]
+    fn priority_fee_uses_requested_limit() {
+        let tx_info = transaction(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(300_000).data,
+                ComputeBudgetInstruction::set_compute_unit_price(1_000_001).data,
+            ],
+            2,
+        );
+        let budget = ComputeBudget::from_transaction(&tx_info).unwrap();
+        assert_eq!(budget.unit_limit, Some(300_000));
+        assert_eq!(budget.num_other_instructions, 2);
+        assert_eq!(budget.effective_unit_limit(), 300_000);
+        // 300_000 units at 1_000_001 micro-lamports is 300_000.3 lamports, rounded up
+        assert_eq!(priority_fee_lamports(&tx_info).unwrap(), 300_001);
+    }
+
+    #[test]
+    fn default_limit_scales_with_other_instructions() {
+        let tx_info = transaction(
+            &[ComputeBudgetInstruction::set_compute_unit_price(50_000).data],
+            3,
+        );
+        let budget = ComputeBudget::from_transaction(&tx_info).unwrap();
+        assert_eq!(budget.unit_limit, None);
+        assert_eq!(
+            budget.effective_unit_limit(),
+            3 * DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
+        );
+        assert_eq!(budget.priority_fee_lamports(), 30_000);
+
+        let tx_info = transaction(
+            &[ComputeBudgetInstruction::set_compute_unit_price(1).data],
+            10,
+        );
+        let budget = ComputeBudget::from_transaction(&tx_info).unwrap();
+        assert_eq!(budget.effective_unit_limit(), MAX_COMPUTE_UNIT_LIMIT);
+        assert_eq!(budget.priority_fee_lamports(), 2);
+    }
+
+    #[test]
+    fn no_price_means_no_priority_fee() {
+        let tx_info = transaction(&[], 1);
+        assert_eq!(priority_fee_lamports(&tx_info).unwrap(), 0);
+        let budget = ComputeBudget::from_transaction(&tx_info).unwrap();
+        assert_eq!(
+            budget,
+            ComputeBudget {
+                num_other_instructions: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_budget_instruction_is_an_error() {
+        let tx_info = transaction(&[vec![9]], 0);
+        let err = ComputeBudget::from_transaction(&tx_info).unwrap_err();
+        assert!(err.to_string().contains("#0"), "{}", err);
+    }
+}
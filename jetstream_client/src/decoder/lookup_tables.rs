@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use futures::future::BoxFuture;
+use jetstream_protos::jetstream::{
+    MessageAddressTableLookup, SubscribeUpdateAccount, SubscribeUpdateTransactionInfo,
+};
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_sdk::message::v0::LoadedAddresses;
+use solana_sdk::pubkey::Pubkey;
+
+use super::bytes;
+
+/// Owner of address lookup table accounts
+pub const LOOKUP_TABLE_PROGRAM_ID: Pubkey = solana_address_lookup_table_interface::program::ID;
+
+/// Source of address lookup table contents that are not cached yet
+pub trait LookupTableFetcher: Send + Sync {
+    /// Addresses stored in `table`, or `None` if the table does not exist
+    fn fetch<'a>(&'a self, table: &'a Pubkey)
+        -> BoxFuture<'a, anyhow::Result<Option<Vec<Pubkey>>>>;
+}
+
+/// Fixed set of lookup tables, e.g. for tests or replays
+#[derive(Debug, Default)]
+pub struct InMemoryLookupTables {
+    tables: RwLock<HashMap<Pubkey, Vec<Pubkey>>>,
+}
+
+impl InMemoryLookupTables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, table: Pubkey, addresses: Vec<Pubkey>) {
+        self.tables
+            .write()
+            .expect("lookup tables lock poisoned")
+            .insert(table, addresses);
+    }
+}
+
+impl LookupTableFetcher for InMemoryLookupTables {
+    fn fetch<'a>(
+        &'a self,
+        table: &'a Pubkey,
+    ) -> BoxFuture<'a, anyhow::Result<Option<Vec<Pubkey>>>> {
+        let addresses = self
+            .tables
+            .read()
+            .expect("lookup tables lock poisoned")
+            .get(table)
+            .cloned();
+        Box::pin(async move { Ok(addresses) })
+    }
+}
+
+/// Resolves `address_table_lookups` of v0 transactions into loaded addresses.
+///
+/// Tables are cached; the cache is fed by account updates of lookup table
+/// accounts and, on a miss, by the optional fetcher.
+#[derive(Clone, Default)]
+pub struct LookupTableResolver {
+    cache: Arc<RwLock<HashMap<Pubkey, Arc<[Pubkey]>>>>,
+    fetcher: Option<Arc<dyn LookupTableFetcher>>,
+}
+
+impl LookupTableResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_fetcher(fetcher: Arc<dyn LookupTableFetcher>) -> Self {
+        Self {
+            cache: Arc::default(),
+            fetcher: Some(fetcher),
+        }
+    }
+
+    /// Cache the contents of a lookup table
+    pub fn insert(&self, table: Pubkey, addresses: Vec<Pubkey>) {
+        self.cache
+            .write()
+            .expect("lookup table cache lock poisoned")
+            .insert(table, addresses.into());
+    }
+
+    /// Forget a cached table, returning whether it was cached
+    pub fn remove(&self, table: &Pubkey) -> bool {
+        self.cache
+            .write()
+            .expect("lookup table cache lock poisoned")
+            .remove(table)
+            .is_some()
+    }
+
+    /// Number of cached tables
+    pub fn len(&self) -> usize {
+        self.cache
+            .read()
+            .expect("lookup table cache lock poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cache the table carried by an account update, or forget it once the
+    /// table is closed. Returns false if the cache was left unchanged.
+    ///
+    /// Deactivated tables stay cached: transactions may use them until they are closed.
+    pub fn update_from_account(&self, account_update: &SubscribeUpdateAccount) -> bool {
+        let Some(account) = &account_update.account else {
+            return false;
+        };
+        let Ok(table) = bytes::pubkey(&account.pubkey) else {
+            return false;
+        };
+        if account.lamports == 0 || account.data.is_empty() {
+            return self.remove(&table);
+        }
+        if account.owner != LOOKUP_TABLE_PROGRAM_ID.as_ref() {
+            return false;
+        }
+
+        match AddressLookupTable::deserialize(&account.data) {
+            Ok(state) => {
+                self.insert(table, state.addresses.to_vec());
+                true
+            }
+            Err(e) => {
+                log::debug!("Ignoring lookup table account {}: {}", table, e);
+                false
+            }
+        }
+    }
+
+    /// Addresses loaded by the transaction's lookups, using only cached tables
+    pub fn resolve_cached(
+        &self,
+        tx_info: &SubscribeUpdateTransactionInfo,
+    ) -> anyhow::Result<LoadedAddresses> {
+        let mut loaded = LoadedAddresses::default();
+        for (index, lookup) in tx_info.address_table_lookups.iter().enumerate() {
+            let table = bytes::lookup_table_key(index, lookup)?;
+            let addresses = self
+                .cached(&table)
+                .ok_or_else(|| anyhow::anyhow!("Lookup table {} is not cached", table))?;
+            load(&table, &addresses, lookup, &mut loaded)?;
+        }
+        Ok(loaded)
+    }
+
+    /// Addresses loaded by the transaction's lookups, fetching tables that are
+    /// missing or too short (they may have been extended since they were cached)
+    pub async fn resolve(
+        &self,
+        tx_info: &SubscribeUpdateTransactionInfo,
+    ) -> anyhow::Result<LoadedAddresses> {
+        let mut loaded = LoadedAddresses::default();
+        for (index, lookup) in tx_info.address_table_lookups.iter().enumerate() {
+            let table = bytes::lookup_table_key(index, lookup)?;
+            let addresses = match self.cached(&table) {
+                Some(addresses) if covers(&addresses, lookup) => addresses,
+                _ => self.fetch(&table).await?,
+            };
+            load(&table, &addresses, lookup, &mut loaded)?;
+        }
+        Ok(loaded)
+    }
+
+    /// Static account keys followed by the loaded writable and readonly addresses
+    pub async fn account_keys(
+        &self,
+        tx_info: &SubscribeUpdateTransactionInfo,
+    ) -> anyhow::Result<Vec<Pubkey>> {
+        let loaded = self.resolve(tx_info).await?;
+        full_account_keys(tx_info, loaded)
+    }
+
+    fn cached(&self, table: &Pubkey) -> Option<Arc<[Pubkey]>> {
+        self.cache
+            .read()
+            .expect("lookup table cache lock poisoned")
+            .get(table)
+            .cloned()
+    }
+
+    async fn fetch(&self, table: &Pubkey) -> anyhow::Result<Arc<[Pubkey]>> {
+        let fetcher = self
+            .fetcher
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Lookup table {} is not cached", table))?;
+        let addresses: Arc<[Pubkey]> = fetcher
+            .fetch(table)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Lookup table {} not found", table))?
+            .into();
+        self.cache
+            .write()
+            .expect("lookup table cache lock poisoned")
+            .insert(*table, addresses.clone());
+        Ok(addresses)
+    }
+}
+
+impl std::fmt::Debug for LookupTableResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LookupTableResolver")
+            .field("tables", &self.len())
+            .field("fetcher", &self.fetcher.is_some())
+            .finish()
+    }
+}
+
+/// Static account keys followed by `loaded` writable and readonly addresses,
+/// matching the index space of `CompiledInstruction` account indexes
+pub fn full_account_keys(
+    tx_info: &SubscribeUpdateTransactionInfo,
+    loaded: LoadedAddresses,
+) -> anyhow::Result<Vec<Pubkey>> {
+    let mut keys = bytes::account_keys(tx_info, loaded.len())?;
+    keys.extend(loaded.writable);
+    keys.extend(loaded.readonly);
+    Ok(keys)
+}
+
+fn covers(addresses: &[Pubkey], lookup: &MessageAddressTableLookup) -> bool {
+    lookup
+        .writable_indexes
+        .iter()
+        .chain(&lookup.readonly_indexes)
+        .all(|index| (*index as usize) < addresses.len())
+}
+
+fn load(
+    table: &Pubkey,
+    addresses: &[Pubkey],
+    lookup: &MessageAddressTableLookup,
+    loaded: &mut LoadedAddresses,
+) -> anyhow::Result<()> {
+    let get = |index: &u8| {
+        addresses.get(*index as usize).copied().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Index {} out of range for lookup table {} with {} address(es)",
+                index,
+                table,
+                addresses.len()
+            )
+        })
+    };
+
+    for index in &lookup.writable_indexes {
+        loaded.writable.push(get(index)?);
+    }
+    for index in &lookup.readonly_indexes {
+        loaded.readonly.push(get(index)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use jetstream_protos::jetstream::SubscribeUpdateAccountInfo;
+    use solana_address_lookup_table_interface::state::LookupTableMeta;
+
+    use super::*;
+
+    fn keys(n: usize) -> Vec<Pubkey> {
+        (0..n).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    fn lookup(table: &Pubkey, writable: &[u8], readonly: &[u8]) -> MessageAddressTableLookup {
+        MessageAddressTableLookup {
+            account_key: table.to_bytes().to_vec(),
+            writable_indexes: writable.to_vec(),
+            readonly_indexes: readonly.to_vec(),
+        }
+    }
+
+    fn transaction(
+        static_keys: &[Pubkey],
+        lookups: Vec<MessageAddressTableLookup>,
+    ) -> SubscribeUpdateTransactionInfo {
+        SubscribeUpdateTransactionInfo {
+            account_keys: static_keys
+                .iter()
+                .map(|key| key.to_bytes().to_vec())
+                .collect(),
+            address_table_lookups: lookups,
+            ..Default::default()
+        }
+    }
+
+    fn table_account(
+        table: &Pubkey,
+        addresses: &[Pubkey],
+        deactivation_slot: u64,
+    ) -> SubscribeUpdateAccount {
+        let data = AddressLookupTable {
+            meta: LookupTableMeta {
+                deactivation_slot,
+                ..LookupTableMeta::new(Pubkey::new_unique())
+            },
+            addresses: Cow::Borrowed(addresses),
+        }
+        .serialize_for_tests()
+        .unwrap();
+        account_update(table, LOOKUP_TABLE_PROGRAM_ID, 1_000_000, data)
+    }
+
+    fn account_update(
+        pubkey: &Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    ) -> SubscribeUpdateAccount {
+        SubscribeUpdateAccount {
+            account: Some(SubscribeUpdateAccountInfo {
+                pubkey: pubkey.to_bytes().to_vec(),
+                lamports,
+                owner: owner.to_bytes().to_vec(),
+                data,
+                ..Default::default()
+            }),
+            slot: 1,
+            is_startup: false,
+        }
+    }
+
+    #[test]
+    fn loads_writable_addresses_of_every_table_before_readonly_ones() {
+        let (table_a, table_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (a, b) = (keys(4), keys(3));
+        let resolver = LookupTableResolver::new();
+        resolver.insert(table_a, a.clone());
+        resolver.insert(table_b, b.clone());
+
+        let static_keys = keys(2);
+        let tx = transaction(
+            &static_keys,
+            vec![
+                lookup(&table_a, &[3, 0], &[1]),
+                lookup(&table_b, &[2], &[0, 1]),
+            ],
+        );
+        let loaded = resolver.resolve_cached(&tx).unwrap();
+        assert_eq!(loaded.writable, [a[3], a[0], b[2]]);
+        assert_eq!(loaded.readonly, [a[1], b[0], b[1]]);
+
+        let full = full_account_keys(&tx, loaded).unwrap();
+        assert_eq!(
+            full,
+            [
+                static_keys[0],
+                static_keys[1],
+                a[3],
+                a[0],
+                b[2],
+                a[1],
+                b[0],
+                b[1]
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_range_index_is_an_error() {
+        let table = Pubkey::new_unique();
+        let resolver = LookupTableResolver::new();
+        resolver.insert(table, keys(2));
+
+        let tx = transaction(&keys(1), vec![lookup(&table, &[0], &[2])]);
+        let err = resolver.resolve_cached(&tx).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Index 2 out of range for lookup table {} with 2 address(es)",
+                table
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn cache_miss_is_fetched_and_cached() {
+        let (table, missing) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let addresses = keys(3);
+        let tx = transaction(&keys(1), vec![lookup(&table, &[2], &[])]);
+
+        let plain = LookupTableResolver::new();
+        let err = plain.resolve_cached(&tx).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Lookup table {} is not cached", table)
+        );
+        assert!(plain.resolve(&tx).await.is_err());
+
+        let fetcher = Arc::new(InMemoryLookupTables::new());
+        fetcher.insert(table, addresses.clone());
+        let resolver = LookupTableResolver::with_fetcher(fetcher);
+        assert!(resolver.resolve_cached(&tx).is_err());
+        assert_eq!(
+            resolver.resolve(&tx).await.unwrap().writable,
+            [addresses[2]]
+        );
+        assert_eq!(resolver.len(), 1);
+        assert_eq!(
+            resolver.resolve_cached(&tx).unwrap().writable,
+            [addresses[2]]
+        );
+
+        let tx = transaction(&keys(1), vec![lookup(&missing, &[0], &[])]);
+        let err = resolver.resolve(&tx).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Lookup table {} not found", missing)
+        );
+    }
+
+    #[tokio::test]
+    async fn short_cached_table_is_refetched() {
+        let table = Pubkey::new_unique();
+        let addresses = keys(5);
+        let fetcher = Arc::new(InMemoryLookupTables::new());
+        fetcher.insert(table, addresses.clone());
+        let resolver = LookupTableResolver::with_fetcher(fetcher);
+        resolver.insert(table, addresses[..2].to_vec());
+
+        let tx = transaction(&keys(1), vec![lookup(&table, &[], &[4])]);
+        assert_eq!(
+            resolver.resolve(&tx).await.unwrap().readonly,
+            [addresses[4]]
+        );
+    }
+
+    #[test]
+    fn account_updates_feed_the_cache() {
+        let table = Pubkey::new_unique();
+        let addresses = keys(3);
+        let resolver = LookupTableResolver::new();
+
+        assert!(resolver.update_from_account(&table_account(&table, &addresses, u64::MAX)));
+        let tx = transaction(&keys(1), vec![lookup(&table, &[1], &[0, 2])]);
+        let loaded = resolver.resolve_cached(&tx).unwrap();
+        assert_eq!(loaded.writable, [addresses[1]]);
+        assert_eq!(loaded.readonly, [addresses[0], addresses[2]]);
+
+        // Accounts owned by other programs are ignored
+        let other = Pubkey::new_unique();
+        assert!(!resolver.update_from_account(&account_update(
+            &other,
+            Pubkey::new_unique(),
+            1,
+            vec![1, 2, 3]
+        )));
+        assert_eq!(resolver.len(), 1);
+    }
+
+    #[test]
+    fn deactivated_table_resolves_until_closed() {
+        let table = Pubkey::new_unique();
+        let addresses = keys(2);
+        let resolver = LookupTableResolver::new();
+        let tx = transaction(&keys(1), vec![lookup(&table, &[0], &[1])]);
+
+        resolver.update_from_account(&table_account(&table, &addresses, u64::MAX));
+        // Deactivation keeps the addresses usable during the cool-down
+        assert!(resolver.update_from_account(&table_account(&table, &addresses, 100)));
+        assert_eq!(
+            resolver.resolve_cached(&tx).unwrap().writable,
+            [addresses[0]]
+        );
+
+        // Closing the table empties and defunds the account
+        assert!(resolver.update_from_account(&account_update(
+            &table,
+            LOOKUP_TABLE_PROGRAM_ID,
+            0,
+            Vec::new()
+        )));
+        assert!(resolver.is_empty());
+        assert!(resolver.resolve_cached(&tx).is_err());
+    }
+}
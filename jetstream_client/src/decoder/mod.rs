@@ -0,0 +1,12 @@
+pub mod accounts;
+pub mod anchor_idl;
+pub mod bytes;
+pub mod compute_budget;
+pub mod lookup_tables;
+pub mod pumpfun;
+pub mod raydium_amm;
+mod reader;
+pub mod registry;
+pub mod spl_token;
+pub mod system_program;
+pub mod transaction;
@@ -0,0 +1,985 @@
+use std::io::{Read, Write};
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use borsh::{BorshDeserialize, BorshSerialize};
+use jetstream_protos::jetstream::instruction::InstructionOneof;
+use jetstream_protos::jetstream::{CompiledInstruction, Instruction};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use super::accounts::{keys_array, TransactionAccounts};
+use super::bytes::BytesError;
+use super::registry::{DecodedInstruction, ProgramDecoder};
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub enum PumpProgramIx {
+    Initialize,
+    SetParams(SetParamsIxArgs),
+    Create(CreateIxArgs),
+    Buy(BuyIxArgs),
+    Sell(SellIxArgs),
+    Withdraw,
+    Migrate,
+    ExtendAccount,
+    SetCreator(SetCreatorIxArgs),
+    CollectCreatorFee,
+    UpdateGlobalAuthority,
+    SetMetaplexCreator,
+}
+
+pub const PUMPFUN_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+
+/// Decoded pump.fun instruction with the accounts it was invoked with
+#[derive(Clone, Debug, PartialEq)]
+pub struct PumpInstruction {
+    pub ix: PumpProgramIx,
+    pub keys: PumpProgramKeys,
+    pub accounts: Vec<AccountMeta>,
+}
+
+impl PumpInstruction {
+    /// Decode instruction data invoked with `accounts`
+    pub fn decode(data: &[u8], accounts: Vec<AccountMeta>) -> std::io::Result<Self> {
+        let ix = PumpProgramIx::deserialize_pumpfun(&PUMPFUN_PROGRAM_ID, data)?;
+        let pubkeys: Vec<Pubkey> = accounts.iter().map(|meta| meta.pubkey).collect();
+        let keys = PumpProgramKeys::from_accounts(&ix, &pubkeys)?;
+        Ok(Self { ix, keys, accounts })
+    }
+}
+
+/// [`ProgramDecoder`] for the pump.fun program
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PumpFunDecoder;
+
+impl ProgramDecoder for PumpFunDecoder {
+    fn program_id(&self) -> Pubkey {
+        PUMPFUN_PROGRAM_ID
+    }
+
+    fn name(&self) -> &str {
+        "pump.fun"
+    }
+
+    fn decode(
+        &self,
+        data: &[u8],
+        accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<DecodedInstruction> {
+        Ok(DecodedInstruction::PumpFun(Box::new(
+            PumpInstruction::decode(data, accounts)?,
+        )))
+    }
+}
+
+/// Named accounts of a pump.fun instruction
+#[derive(Clone, Debug, PartialEq)]
+pub enum PumpProgramKeys {
+    Initialize(InitializeKeys),
+    SetParams(SetParamsKeys),
+    Create(CreateKeys),
+    Buy(BuyKeys),
+    Sell(SellKeys),
+    Withdraw(WithdrawKeys),
+    ExtendAccount(ExtendAccountKeys),
+    /// Instruction without named accounts; see `PumpInstruction::accounts`
+    Unnamed,
+}
+
+impl PumpProgramKeys {
+    /// Name the `accounts` of `ix`; fails if there are fewer than the instruction expects
+    pub fn from_accounts(ix: &PumpProgramIx, accounts: &[Pubkey]) -> std::io::Result<Self> {
+        match ix {
+            PumpProgramIx::Initialize => Ok(Self::Initialize(keys_array(accounts)?.into())),
+            PumpProgramIx::SetParams(_) => Ok(Self::SetParams(keys_array(accounts)?.into())),
+            PumpProgramIx::Create(_) => Ok(Self::Create(keys_array(accounts)?.into())),
+            PumpProgramIx::Buy(_) => Ok(Self::Buy(keys_array(accounts)?.into())),
+            PumpProgramIx::Sell(_) => Ok(Self::Sell(keys_array(accounts)?.into())),
+            PumpProgramIx::Withdraw => Ok(Self::Withdraw(keys_array(accounts)?.into())),
+            PumpProgramIx::ExtendAccount => Ok(Self::ExtendAccount(keys_array(accounts)?.into())),
+            _ => Ok(Self::Unnamed),
+        }
+    }
+}
+
+/// Object safe wrapper around `BorshSerialize`
+trait BorshSerializeDyn {
+    fn serialize_into(&self, data: &mut Vec<u8>) -> std::io::Result<()>;
+}
+
+impl<T: BorshSerialize> BorshSerializeDyn for T {
+    fn serialize_into(&self, data: &mut Vec<u8>) -> std::io::Result<()> {
+        self.serialize(data)
+    }
+}
+
+impl PumpProgramIx {
+    /// Decode instruction data sent to `program_id`
+    pub fn deserialize_pumpfun(program_id: &Pubkey, buf: &[u8]) -> std::io::Result<Self> {
+        if *program_id != PUMPFUN_PROGRAM_ID {
+            return Err(std::io::Error::other("Not a PumpFun program instruction"));
+        }
+
+        let mut reader = buf;
+        let mut maybe_discm = [0u8; 8];
+        reader.read_exact(&mut maybe_discm)?;
+
+        match maybe_discm {
+            INITIALIZE_IX_DISCM => Ok(Self::Initialize),
+            SET_PARAMS_IX_DISCM => Ok(Self::SetParams(SetParamsIxArgs::deserialize(&mut reader)?)),
+            CREATE_IX_DISCM => Ok(Self::Create(CreateIxArgs::deserialize(&mut reader)?)),
+            BUY_IX_DISCM => Ok(Self::Buy(BuyIxArgs::deserialize(&mut reader)?)),
+            SELL_IX_DISCM => Ok(Self::Sell(SellIxArgs::deserialize(&mut reader)?)),
+            WITHDRAW_IX_DISCM => Ok(Self::Withdraw),
+            MIGRATE_IX_DISCM => Ok(Self::Migrate),
+            EXTEND_ACCOUNT_IX_DISCM => Ok(Self::ExtendAccount),
+            SET_CREATOR_IX_DISCM => Ok(Self::SetCreator(SetCreatorIxArgs::deserialize(
+                &mut reader,
+            )?)),
+            COLLECT_CREATOR_FEE_IX_DISCM => Ok(Self::CollectCreatorFee),
+            UPDATE_GLOBAL_AUTHORITY_IX_DISCM => Ok(Self::UpdateGlobalAuthority),
+            SET_METAPLEX_CREATOR_IX_DISCM => Ok(Self::SetMetaplexCreator),
+            _ => Err(std::io::Error::other(format!(
+                "PumpFun discm {:?} not found",
+                maybe_discm
+            ))),
+        }
+    }
+
+    /// Instruction data: the discriminator followed by the borsh encoded args
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let (discm, args): (_, Option<&dyn BorshSerializeDyn>) = match self {
+            Self::Initialize => (INITIALIZE_IX_DISCM, None),
+            Self::SetParams(args) => (SET_PARAMS_IX_DISCM, Some(args)),
+            Self::Create(args) => (CREATE_IX_DISCM, Some(args)),
+            Self::Buy(args) => (BUY_IX_DISCM, Some(args)),
+            Self::Sell(args) => (SELL_IX_DISCM, Some(args)),
+            Self::Withdraw => (WITHDRAW_IX_DISCM, None),
+            Self::Migrate => (MIGRATE_IX_DISCM, None),
+            Self::ExtendAccount => (EXTEND_ACCOUNT_IX_DISCM, None),
+            Self::SetCreator(args) => (SET_CREATOR_IX_DISCM, Some(args)),
+            Self::CollectCreatorFee => (COLLECT_CREATOR_FEE_IX_DISCM, None),
+            Self::UpdateGlobalAuthority => (UPDATE_GLOBAL_AUTHORITY_IX_DISCM, None),
+            Self::SetMetaplexCreator => (SET_METAPLEX_CREATOR_IX_DISCM, None),
+        };
+        let mut data = discm.to_vec();
+        if let Some(args) = args {
+            args.serialize_into(&mut data)?;
+        }
+        Ok(data)
+    }
+
+    /// Decode `instruction` if it invokes the pump.fun program, `None` otherwise
+    pub fn decode_instruction(
+        accounts: &TransactionAccounts,
+        instruction: &CompiledInstruction,
+    ) -> std::io::Result<Option<PumpInstruction>> {
+        match accounts.program_id(instruction) {
+            Some(program_id) if *program_id == PUMPFUN_PROGRAM_ID => {}
+            _ => return Ok(None),
+        }
+
+        let accounts = accounts
+            .account_metas(instruction)
+            .map_err(std::io::Error::other)?;
+        PumpInstruction::decode(&instruction.data, accounts).map(Some)
+    }
+
+    /// Convert an instruction of the parsed stream, `None` if it carries none.
+    /// The parsed `Create` does not carry the creator, and the parsed `SetParams`
+    /// follows the retired fee recipient layout, so it converts to `None`.
+    pub fn from_parsed(instruction: &Instruction) -> Result<Option<Self>, BytesError> {
+        let Some(instruction) = &instruction.instruction_oneof else {
+            return Ok(None);
+        };
+        let ix = match instruction {
+            InstructionOneof::Initialize(_) => Self::Initialize,
+            InstructionOneof::SetParams(_) => return Ok(None),
+            InstructionOneof::Create(args) => Self::Create(CreateIxArgs {
+                name: args.name.clone(),
+                symbol: args.symbol.clone(),
+                uri: args.uri.clone(),
+                creator: None,
+            }),
+            InstructionOneof::Buy(args) => Self::Buy(BuyIxArgs {
+                amount: args.amount,
+                max_sol_cost: args.max_sol_cost,
+            }),
+            InstructionOneof::Sell(args) => Self::Sell(SellIxArgs {
+                amount: args.amount,
+                min_sol_output: args.min_sol_output,
+            }),
+            InstructionOneof::Withdraw(_) => Self::Withdraw,
+        };
+        Ok(Some(ix))
+    }
+}
+
+pub const INITIALIZE_IX_DISCM: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+pub const INITIALIZE_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InitializeKeys {
+    pub global: Pubkey,
+    pub user: Pubkey,
+    pub system_program: Pubkey,
+}
+impl From<[Pubkey; INITIALIZE_IX_ACCOUNTS_LEN]> for InitializeKeys {
+    fn from(pubkeys: [Pubkey; INITIALIZE_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            global: pubkeys[0],
+            user: pubkeys[1],
+            system_program: pubkeys[2],
+        }
+    }
+}
+
+pub const SET_PARAMS_IX_DISCM: [u8; 8] = [27, 234, 178, 52, 147, 2, 187, 141];
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct SetParamsIxArgs {
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+    pub withdraw_authority: Pubkey,
+    pub enable_migrate: bool,
+    pub pool_migration_fee: u64,
+    pub creator_fee_basis_points: u64,
+    pub set_creator_authority: Pubkey,
+}
+
+pub const SET_PARAMS_IX_ACCOUNTS_LEN: usize = 4;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SetParamsKeys {
+    pub global: Pubkey,
+    pub authority: Pubkey,
+    pub event_authority: Pubkey,
+    pub program: Pubkey,
+}
+impl From<[Pubkey; SET_PARAMS_IX_ACCOUNTS_LEN]> for SetParamsKeys {
+    fn from(pubkeys: [Pubkey; SET_PARAMS_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            global: pubkeys[0],
+            authority: pubkeys[1],
+            event_authority: pubkeys[2],
+            program: pubkeys[3],
+        }
+    }
+}
+
+pub const CREATE_IX_DISCM: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CreateIxArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Trailing arg added with creator fees; absent from older `Create` data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<Pubkey>,
+}
+
+impl BorshSerialize for CreateIxArgs {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.name.serialize(writer)?;
+        self.symbol.serialize(writer)?;
+        self.uri.serialize(writer)?;
+        if let Some(creator) = &self.creator {
+            creator.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for CreateIxArgs {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let name = String::deserialize_reader(reader)?;
+        let symbol = String::deserialize_reader(reader)?;
+        let uri = String::deserialize_reader(reader)?;
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest)?;
+        let creator = match rest.len() {
+            0 => None,
+            _ => Some(Pubkey::try_from_slice(&rest)?),
+        };
+        Ok(Self {
+            name,
+            symbol,
+            uri,
+            creator,
+        })
+    }
+}
+
+pub const CREATE_IX_ACCOUNTS_LEN: usize = 14;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CreateKeys {
+    pub mint: Pubkey,
+    pub mint_authority: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    pub global: Pubkey,
+    pub mpl_token_metadata: Pubkey,
+    pub metadata: Pubkey,
+    pub user: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub associated_token_program: Pubkey,
+    pub rent: Pubkey,
+    pub event_authority: Pubkey,
+    pub program: Pubkey,
+}
+impl From<[Pubkey; CREATE_IX_ACCOUNTS_LEN]> for CreateKeys {
+    fn from(pubkeys: [Pubkey; CREATE_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            mint: pubkeys[0],
+            mint_authority: pubkeys[1],
+            bonding_curve: pubkeys[2],
+            associated_bonding_curve: pubkeys[3],
+            global: pubkeys[4],
+            mpl_token_metadata: pubkeys[5],
+            metadata: pubkeys[6],
+            user: pubkeys[7],
+            system_program: pubkeys[8],
+            token_program: pubkeys[9],
+            associated_token_program: pubkeys[10],
+            rent: pubkeys[11],
+            event_authority: pubkeys[12],
+            program: pubkeys[13],
+        }
+    }
+}
+
+pub const SELL_IX_DISCM: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct SellIxArgs {
+    pub amount: u64,
+    pub min_sol_output: u64,
+}
+
+pub const SELL_IX_ACCOUNTS_LEN: usize = 12;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SellKeys {
+    pub global: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    pub associated_user: Pubkey,
+    pub user: Pubkey,
+    pub system_program: Pubkey,
+    pub creator_vault: Pubkey,
+    pub token_program: Pubkey,
+    pub event_authority: Pubkey,
+    pub program: Pubkey,
+}
+impl From<[Pubkey; SELL_IX_ACCOUNTS_LEN]> for SellKeys {
+    fn from(pubkeys: [Pubkey; SELL_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            global: pubkeys[0],
+            fee_recipient: pubkeys[1],
+            mint: pubkeys[2],
+            bonding_curve: pubkeys[3],
+            associated_bonding_curve: pubkeys[4],
+            associated_user: pubkeys[5],
+            user: pubkeys[6],
+            system_program: pubkeys[7],
+            creator_vault: pubkeys[8],
+            token_program: pubkeys[9],
+            event_authority: pubkeys[10],
+            program: pubkeys[11],
+        }
+    }
+}
+
+pub const BUY_IX_DISCM: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct BuyIxArgs {
+    pub amount: u64,
+    pub max_sol_cost: u64,
+}
+
+pub const BUY_IX_ACCOUNTS_LEN: usize = 12;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BuyKeys {
+    pub global: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    pub associated_user: Pubkey,
+    pub user: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub creator_vault: Pubkey,
+    pub event_authority: Pubkey,
+    pub program: Pubkey,
+}
+impl From<[Pubkey; BUY_IX_ACCOUNTS_LEN]> for BuyKeys {
+    fn from(pubkeys: [Pubkey; BUY_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            global: pubkeys[0],
+            fee_recipient: pubkeys[1],
+            mint: pubkeys[2],
+            bonding_curve: pubkeys[3],
+            associated_bonding_curve: pubkeys[4],
+            associated_user: pubkeys[5],
+            user: pubkeys[6],
+            system_program: pubkeys[7],
+            token_program: pubkeys[8],
+            creator_vault: pubkeys[9],
+            event_authority: pubkeys[10],
+            program: pubkeys[11],
+        }
+    }
+}
+
+pub const WITHDRAW_IX_DISCM: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+pub const WITHDRAW_IX_ACCOUNTS_LEN: usize = 12;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WithdrawKeys {
+    pub global: Pubkey,
+    pub last_withdraw: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    pub associated_user: Pubkey,
+    pub user: Pubkey,
+    pub system_program: Pubkey,
+    pub token_program: Pubkey,
+    pub rent: Pubkey,
+    pub event_authority: Pubkey,
+    pub program: Pubkey,
+}
+impl From<[Pubkey; WITHDRAW_IX_ACCOUNTS_LEN]> for WithdrawKeys {
+    fn from(pubkeys: [Pubkey; WITHDRAW_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            global: pubkeys[0],
+            last_withdraw: pubkeys[1],
+            mint: pubkeys[2],
+            bonding_curve: pubkeys[3],
+            associated_bonding_curve: pubkeys[4],
+            associated_user: pubkeys[5],
+            user: pubkeys[6],
+            system_program: pubkeys[7],
+            token_program: pubkeys[8],
+            rent: pubkeys[9],
+            event_authority: pubkeys[10],
+            program: pubkeys[11],
+        }
+    }
+}
+
+pub const MIGRATE_IX_DISCM: [u8; 8] = [155, 234, 231, 146, 236, 158, 162, 30];
+
+pub const EXTEND_ACCOUNT_IX_DISCM: [u8; 8] = [234, 102, 194, 203, 150, 72, 62, 229];
+pub const EXTEND_ACCOUNT_IX_ACCOUNTS_LEN: usize = 5;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ExtendAccountKeys {
+    pub account: Pubkey,
+    pub user: Pubkey,
+    pub system_program: Pubkey,
+    pub event_authority: Pubkey,
+    pub program: Pubkey,
+}
+impl From<[Pubkey; EXTEND_ACCOUNT_IX_ACCOUNTS_LEN]> for ExtendAccountKeys {
+    fn from(pubkeys: [Pubkey; EXTEND_ACCOUNT_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            account: pubkeys[0],
+            user: pubkeys[1],
+            system_program: pubkeys[2],
+            event_authority: pubkeys[3],
+            program: pubkeys[4],
+        }
+    }
+}
+
+pub const SET_CREATOR_IX_DISCM: [u8; 8] = [254, 148, 255, 112, 207, 142, 170, 165];
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct SetCreatorIxArgs {
+    pub creator: Pubkey,
+}
+
+pub const COLLECT_CREATOR_FEE_IX_DISCM: [u8; 8] = [20, 22, 86, 123, 198, 28, 219, 132];
+pub const UPDATE_GLOBAL_AUTHORITY_IX_DISCM: [u8; 8] = [227, 181, 74, 196, 208, 21, 97, 213];
+pub const SET_METAPLEX_CREATOR_IX_DISCM: [u8; 8] = [138, 96, 174, 217, 48, 85, 197, 246];
+
+/// Tag prefixing the data of Anchor `emit_cpi!` self-invocations
+pub const EVENT_IX_TAG: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+/// Prefix of the log line carrying an Anchor `emit!` event
+const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Event emitted by the pump.fun program
+#[derive(Clone, Debug, PartialEq)]
+pub enum PumpProgramEvent {
+    Trade(TradeEvent),
+    Create(CreateEvent),
+    Complete(CompleteEvent),
+}
+
+impl PumpProgramEvent {
+    /// Decode an event discriminator followed by the borsh encoded event
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm = [0u8; 8];
+        reader.read_exact(&mut maybe_discm)?;
+
+        match maybe_discm {
+            TRADE_EVENT_DISCM => Ok(Self::Trade(TradeEvent::deserialize(&mut reader)?)),
+            CREATE_EVENT_DISCM => Ok(Self::Create(CreateEvent::deserialize(&mut reader)?)),
+            COMPLETE_EVENT_DISCM => Ok(Self::Complete(CompleteEvent::deserialize(&mut reader)?)),
+            _ => Err(std::io::Error::other(format!(
+                "PumpFun event discm {:?} not found",
+                maybe_discm
+            ))),
+        }
+    }
+
+    /// Decode the data of an `emit_cpi!` inner instruction sent to `program_id`
+    pub fn from_cpi(program_id: &Pubkey, data: &[u8]) -> std::io::Result<Self> {
+        if *program_id != PUMPFUN_PROGRAM_ID {
+            return Err(std::io::Error::other("Not a PumpFun program instruction"));
+        }
+        let event = data
+            .strip_prefix(EVENT_IX_TAG.as_slice())
+            .ok_or_else(|| std::io::Error::other("Not an event instruction"))?;
+        Self::deserialize(event)
+    }
+
+    /// Decode a `Program data: <base64>` log line; `None` if the line carries no data
+    pub fn from_log(line: &str) -> Option<std::io::Result<Self>> {
+        let encoded = line.strip_prefix(PROGRAM_DATA_LOG_PREFIX)?;
+        Some(
+            BASE64_STANDARD
+                .decode(encoded.trim())
+                .map_err(std::io::Error::other)
+                .and_then(|data| Self::deserialize(&data)),
+        )
+    }
+
+    /// Event discriminator followed by the borsh encoded event
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        match self {
+            Self::Trade(event) => {
+                data.extend_from_slice(&TRADE_EVENT_DISCM);
+                event.serialize(&mut data)?;
+            }
+            Self::Create(event) => {
+                data.extend_from_slice(&CREATE_EVENT_DISCM);
+                event.serialize(&mut data)?;
+            }
+            Self::Complete(event) => {
+                data.extend_from_slice(&COMPLETE_EVENT_DISCM);
+                event.serialize(&mut data)?;
+            }
+        }
+        Ok(data)
+    }
+}
+
+pub const TRADE_EVENT_DISCM: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct TradeEvent {
+    pub mint: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: Pubkey,
+    pub timestamp: i64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+}
+
+pub const CREATE_EVENT_DISCM: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct CreateEvent {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+}
+
+pub const COMPLETE_EVENT_DISCM: [u8; 8] = [95, 114, 97, 156, 212, 46, 152, 8];
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct CompleteEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: Pubkey = pubkey!("7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr");
+    const USER: Pubkey = pubkey!("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM");
+    const BONDING_CURVE: Pubkey = pubkey!("HMU77m6WSL9Xew9YvVCgz1hLuhzamz74eD9avi4XPdr");
+
+    /// Instruction or event data laid out as the program writes it:
+    /// the discriminator followed by the little-endian borsh fields
+    fn data(discm: [u8; 8], fields: &[&[u8]]) -> Vec<u8> {
+        let mut data = discm.to_vec();
+        for field in fields {
+            data.extend_from_slice(field);
+        }
+        data
+    }
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut data = (value.len() as u32).to_le_bytes().to_vec();
+        data.extend_from_slice(value.as_bytes());
+        data
+    }
+
+    fn decode(data: &[u8]) -> PumpProgramIx {
+        let ix = PumpProgramIx::deserialize_pumpfun(&PUMPFUN_PROGRAM_ID, data).unwrap();
+        assert_eq!(ix.try_to_vec().unwrap(), data);
+        ix
+    }
+
+    fn decode_event(data: &[u8]) -> PumpProgramEvent {
+        let cpi = [EVENT_IX_TAG.as_slice(), data].concat();
+        let event = PumpProgramEvent::from_cpi(&PUMPFUN_PROGRAM_ID, &cpi).unwrap();
+        assert_eq!(event.try_to_vec().unwrap(), data);
+        event
+    }
+
+    #[test]
+    fn create_round_trips() {
+        let data = data(
+            CREATE_IX_DISCM,
+            &[
+                &string("Jetstream"),
+                &string("JET"),
+                &string("https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+                USER.as_ref(),
+            ],
+        );
+        assert_eq!(
+            decode(&data),
+            PumpProgramIx::Create(CreateIxArgs {
+                name: "Jetstream".to_string(),
+                symbol: "JET".to_string(),
+                uri: "https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+                    .to_string(),
+                creator: Some(USER),
+            })
+        );
+    }
+
+    #[test]
+    fn create_before_creator_fees_decodes() {
+        let data = data(CREATE_IX_DISCM, &[&string("A"), &string("B"), &string("C")]);
+        let PumpProgramIx::Create(args) = decode(&data) else {
+            panic!("expected create");
+        };
+        assert_eq!(args.creator, None);
+        assert!(serde_json::to_value(&args)
+            .unwrap()
+            .get("creator")
+            .is_none());
+
+        let truncated = [data.as_slice(), &[1; 31]].concat();
+        assert!(PumpProgramIx::deserialize_pumpfun(&PUMPFUN_PROGRAM_ID, &truncated).is_err());
+    }
+
+    #[test]
+    fn parsed_create_has_no_creator_and_set_params_is_skipped() {
+        use jetstream_protos::jetstream::{Create, SetParams};
+
+        let create = Instruction {
+            instruction_oneof: Some(InstructionOneof::Create(Create {
+                name: "A".to_string(),
+                symbol: "B".to_string(),
+                uri: "C".to_string(),
+            })),
+        };
+        let Some(PumpProgramIx::Create(args)) = PumpProgramIx::from_parsed(&create).unwrap() else {
+            panic!("expected create");
+        };
+        assert_eq!(args.creator, None);
+
+        let set_params = Instruction {
+            instruction_oneof: Some(InstructionOneof::SetParams(SetParams::default())),
+        };
+        assert_eq!(PumpProgramIx::from_parsed(&set_params).unwrap(), None);
+    }
+
+    #[test]
+    fn buy_and_sell_round_trip() {
+        let buy = data(
+            BUY_IX_DISCM,
+            &[
+                &35_210_992_118u64.to_le_bytes(),
+                &1_030_000_000u64.to_le_bytes(),
+            ],
+        );
+        assert_eq!(
+            decode(&buy),
+            PumpProgramIx::Buy(BuyIxArgs {
+                amount: 35_210_992_118,
+                max_sol_cost: 1_030_000_000,
+            })
+        );
+
+        let sell = data(
+            SELL_IX_DISCM,
+            &[&35_210_992_118u64.to_le_bytes(), &0u64.to_le_bytes()],
+        );
+        assert_eq!(
+            decode(&sell),
+            PumpProgramIx::Sell(SellIxArgs {
+                amount: 35_210_992_118,
+                min_sol_output: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn admin_instructions_round_trip() {
+        let set_params = data(
+            SET_PARAMS_IX_DISCM,
+            &[
+                &1_073_000_000_000_000u64.to_le_bytes(),
+                &30_000_000_000u64.to_le_bytes(),
+                &793_100_000_000_000u64.to_le_bytes(),
+                &1_000_000_000_000_000u64.to_le_bytes(),
+                &95u64.to_le_bytes(),
+                USER.as_ref(),
+                &[1],
+                &15_000_001u64.to_le_bytes(),
+                &5u64.to_le_bytes(),
+                MINT.as_ref(),
+            ],
+        );
+        assert_eq!(
+            decode(&set_params),
+            PumpProgramIx::SetParams(SetParamsIxArgs {
+                initial_virtual_token_reserves: 1_073_000_000_000_000,
+                initial_virtual_sol_reserves: 30_000_000_000,
+                initial_real_token_reserves: 793_100_000_000_000,
+                token_total_supply: 1_000_000_000_000_000,
+                fee_basis_points: 95,
+                withdraw_authority: USER,
+                enable_migrate: true,
+                pool_migration_fee: 15_000_001,
+                creator_fee_basis_points: 5,
+                set_creator_authority: MINT,
+            })
+        );
+
+        let set_creator = data(SET_CREATOR_IX_DISCM, &[USER.as_ref()]);
+        assert_eq!(
+            decode(&set_creator),
+            PumpProgramIx::SetCreator(SetCreatorIxArgs { creator: USER })
+        );
+
+        for (discm, ix) in [
+            (INITIALIZE_IX_DISCM, PumpProgramIx::Initialize),
+            (WITHDRAW_IX_DISCM, PumpProgramIx::Withdraw),
+            (MIGRATE_IX_DISCM, PumpProgramIx::Migrate),
+            (EXTEND_ACCOUNT_IX_DISCM, PumpProgramIx::ExtendAccount),
+            (
+                COLLECT_CREATOR_FEE_IX_DISCM,
+                PumpProgramIx::CollectCreatorFee,
+            ),
+            (
+                UPDATE_GLOBAL_AUTHORITY_IX_DISCM,
+                PumpProgramIx::UpdateGlobalAuthority,
+            ),
+            (
+                SET_METAPLEX_CREATOR_IX_DISCM,
+                PumpProgramIx::SetMetaplexCreator,
+            ),
+        ] {
+            assert_eq!(decode(&discm), ix);
+        }
+    }
+
+    /// Base58 instruction and event data, as block explorers show it
+    const CREATE_DATA: &str = "4fEnDdscaoZM2g9mk2zeVjaCp1L279ASgZGepe1ake4hMpHVYCRZo87r2E2QqdNN2283AVAkr94LcE6vb7dASVLcjQxcEDVMrfbiNc3Z5iCXMNaQkX6kRuPWtcUZgCm2Ny6PZddTuFe3SfprUSzZAk9j497Sgh2GE1L1jjDjC45xQrdeirm";
+    const BUY_DATA: &str = "AJTQ2h9DXrC9XkVSPHtxxfyJK3YyT8hkj";
+    const SELL_DATA: &str = "5jRcjdixRUDkTZNG6Ciqr9zwJGyb4EbnF";
+    const TRADE_EVENT_DATA: &str = "3iLLFK7mC2FqKiscf6XtP5hc4HLxjkHurEtMWwB7X2LvEJtLssn7NruHLbHEr67vhiYpXzKdQFWsKaSKSWwVtCZAer2YAN1WSVcJGiiBzL22BCD62QmAXppN19vJxyk1uwDAKo7RJ4A2B5ZzdP7KU6gUVaF";
+
+    fn base58(data: &str) -> Vec<u8> {
+        solana_sdk::bs58::decode(data).into_vec().unwrap()
+    }
+
+    #[test]
+    fn encoded_instruction_data_round_trips() {
+        let PumpProgramIx::Create(create) = decode(&base58(CREATE_DATA)) else {
+            panic!("expected create");
+        };
+        assert_eq!(
+            (create.name.as_str(), create.symbol.as_str()),
+            ("Jetstream", "JET")
+        );
+        assert_eq!(create.creator, Some(USER));
+
+        assert_eq!(
+            decode(&base58(BUY_DATA)),
+            PumpProgramIx::Buy(BuyIxArgs {
+                amount: 35_210_992_118,
+                max_sol_cost: 1_030_000_000,
+            })
+        );
+        assert_eq!(
+            decode(&base58(SELL_DATA)),
+            PumpProgramIx::Sell(SellIxArgs {
+                amount: 35_210_992_118,
+                min_sol_output: 985_000_000,
+            })
+        );
+
+        let PumpProgramEvent::Trade(trade) = decode_event(&base58(TRADE_EVENT_DATA)) else {
+            panic!("expected trade event");
+        };
+        assert_eq!((trade.mint, trade.user, trade.is_buy), (MINT, USER, true));
+        assert_eq!(trade.sol_amount, 1_000_000_000);
+        assert_eq!(trade.timestamp, 1_718_000_000);
+    }
+
+    #[test]
+    fn unknown_discriminator_and_program_are_rejected() {
+        assert!(PumpProgramIx::deserialize_pumpfun(&PUMPFUN_PROGRAM_ID, &[0; 8]).is_err());
+        assert!(PumpProgramIx::deserialize_pumpfun(&MINT, &BUY_IX_DISCM).is_err());
+    }
+
+    #[test]
+    fn buy_names_its_accounts() {
+        let pubkeys: Vec<Pubkey> = (0..BUY_IX_ACCOUNTS_LEN)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        let accounts = pubkeys
+            .iter()
+            .map(|pubkey| AccountMeta::new_readonly(*pubkey, false))
+            .collect();
+        let buy = data(BUY_IX_DISCM, &[&1u64.to_le_bytes(), &2u64.to_le_bytes()]);
+
+        let ix = PumpInstruction::decode(&buy, accounts).unwrap();
+        let PumpProgramKeys::Buy(keys) = ix.keys else {
+            panic!("expected buy keys");
+        };
+        assert_eq!(keys.mint, pubkeys[2]);
+        assert_eq!(keys.user, pubkeys[6]);
+        assert_eq!(keys.creator_vault, pubkeys[9]);
+        assert_eq!(keys.program, pubkeys[11]);
+
+        let short = pubkeys[..BUY_IX_ACCOUNTS_LEN - 1]
+            .iter()
+            .map(|pubkey| AccountMeta::new_readonly(*pubkey, false))
+            .collect();
+        assert!(PumpInstruction::decode(&buy, short).is_err());
+    }
+
+    #[test]
+    fn trade_event_round_trips() {
+        let data = data(
+            TRADE_EVENT_DISCM,
+            &[
+                MINT.as_ref(),
+                &1_000_000_000u64.to_le_bytes(),
+                &35_210_992_118u64.to_le_bytes(),
+                &[1],
+                USER.as_ref(),
+                &1_718_000_000i64.to_le_bytes(),
+                &31_000_000_000u64.to_le_bytes(),
+                &1_038_000_000_000_000u64.to_le_bytes(),
+            ],
+        );
+        assert_eq!(
+            decode_event(&data),
+            PumpProgramEvent::Trade(TradeEvent {
+                mint: MINT,
+                sol_amount: 1_000_000_000,
+                token_amount: 35_210_992_118,
+                is_buy: true,
+                user: USER,
+                timestamp: 1_718_000_000,
+                virtual_sol_reserves: 31_000_000_000,
+                virtual_token_reserves: 1_038_000_000_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn create_event_round_trips() {
+        let data = data(
+            CREATE_EVENT_DISCM,
+            &[
+                &string("Jetstream"),
+                &string("JET"),
+                &string("https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+                MINT.as_ref(),
+                BONDING_CURVE.as_ref(),
+                USER.as_ref(),
+            ],
+        );
+        assert_eq!(
+            decode_event(&data),
+            PumpProgramEvent::Create(CreateEvent {
+                name: "Jetstream".to_string(),
+                symbol: "JET".to_string(),
+                uri: "https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+                    .to_string(),
+                mint: MINT,
+                bonding_curve: BONDING_CURVE,
+                user: USER,
+            })
+        );
+    }
+
+    #[test]
+    fn complete_event_round_trips_through_cpi_and_log() {
+        let data = data(
+            COMPLETE_EVENT_DISCM,
+            &[
+                USER.as_ref(),
+                MINT.as_ref(),
+                BONDING_CURVE.as_ref(),
+                &1_718_000_042i64.to_le_bytes(),
+            ],
+        );
+        let event = decode_event(&data);
+        assert_eq!(
+            event,
+            PumpProgramEvent::Complete(CompleteEvent {
+                user: USER,
+                mint: MINT,
+                bonding_curve: BONDING_CURVE,
+                timestamp: 1_718_000_042,
+            })
+        );
+
+        let line = format!("Program data: {}", BASE64_STANDARD.encode(&data));
+        assert_eq!(PumpProgramEvent::from_log(&line).unwrap().unwrap(), event);
+        assert!(PumpProgramEvent::from_log("Program log: Instruction: Buy").is_none());
+    }
+
+    #[test]
+    fn event_cpi_requires_the_event_tag() {
+        let data = data(COMPLETE_EVENT_DISCM, &[&[0; 104]]);
+        assert!(PumpProgramEvent::from_cpi(&PUMPFUN_PROGRAM_ID, &data).is_err());
+        let cpi = [EVENT_IX_TAG.as_slice(), &data].concat();
+        assert!(PumpProgramEvent::from_cpi(&MINT, &cpi).is_err());
+    }
+}
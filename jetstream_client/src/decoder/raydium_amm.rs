@@ -0,0 +1,546 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use super::accounts::keys_array;
+use super::registry::{DecodedInstruction, ProgramDecoder};
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub enum RaydiumAmmIx {
+    Initialize2(Initialize2IxArgs),
+    Deposit(DepositIxArgs),
+    Withdraw(WithdrawIxArgs),
+    SwapBaseIn(SwapBaseInIxArgs),
+    SwapBaseOut(SwapBaseOutIxArgs),
+}
+
+pub const RAYDIUM_AMM_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+impl RaydiumAmmIx {
+    /// Decode instruction data: a one byte tag followed by the borsh encoded args
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let (tag, mut reader) = buf
+            .split_first()
+            .ok_or_else(|| std::io::Error::other("Empty Raydium AMM instruction"))?;
+
+        match *tag {
+            INITIALIZE2_IX_TAG => Ok(Self::Initialize2(Initialize2IxArgs::deserialize(
+                &mut reader,
+            )?)),
+            DEPOSIT_IX_TAG => Ok(Self::Deposit(DepositIxArgs::deserialize(&mut reader)?)),
+            WITHDRAW_IX_TAG => Ok(Self::Withdraw(WithdrawIxArgs::deserialize(&mut reader)?)),
+            SWAP_BASE_IN_IX_TAG => Ok(Self::SwapBaseIn(SwapBaseInIxArgs::deserialize(
+                &mut reader,
+            )?)),
+            SWAP_BASE_OUT_IX_TAG => Ok(Self::SwapBaseOut(SwapBaseOutIxArgs::deserialize(
+                &mut reader,
+            )?)),
+            _ => Err(std::io::Error::other(format!(
+                "Raydium AMM instruction tag {} not supported",
+                tag
+            ))),
+        }
+    }
+}
+
+/// Decoded Raydium AMM v4 instruction with the accounts it was invoked with
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaydiumAmmInstruction {
+    pub ix: RaydiumAmmIx,
+    pub keys: RaydiumAmmKeys,
+    pub accounts: Vec<AccountMeta>,
+}
+
+impl RaydiumAmmInstruction {
+    /// Decode instruction data invoked with `accounts`
+    pub fn decode(data: &[u8], accounts: Vec<AccountMeta>) -> std::io::Result<Self> {
+        let ix = RaydiumAmmIx::deserialize(data)?;
+        let pubkeys: Vec<Pubkey> = accounts.iter().map(|meta| meta.pubkey).collect();
+        let keys = RaydiumAmmKeys::from_accounts(&ix, &pubkeys)?;
+        Ok(Self { ix, keys, accounts })
+    }
+}
+
+/// Named accounts of a Raydium AMM v4 instruction
+#[derive(Clone, Debug, PartialEq)]
+pub enum RaydiumAmmKeys {
+    Initialize2(Initialize2Keys),
+    Deposit(DepositKeys),
+    Withdraw(WithdrawKeys),
+    Swap(SwapKeys),
+}
+
+impl RaydiumAmmKeys {
+    /// Name the `accounts` of `ix`; fails if there are fewer than the instruction expects
+    pub fn from_accounts(ix: &RaydiumAmmIx, accounts: &[Pubkey]) -> std::io::Result<Self> {
+        match ix {
+            RaydiumAmmIx::Initialize2(_) => Ok(Self::Initialize2(keys_array(accounts)?.into())),
+            RaydiumAmmIx::Deposit(_) => Ok(Self::Deposit(keys_array(accounts)?.into())),
+            RaydiumAmmIx::Withdraw(_) => Ok(Self::Withdraw(keys_array(accounts)?.into())),
+            RaydiumAmmIx::SwapBaseIn(_) | RaydiumAmmIx::SwapBaseOut(_) => {
+                SwapKeys::from_accounts(accounts).map(Self::Swap)
+            }
+        }
+    }
+}
+
+/// [`ProgramDecoder`] for the Raydium AMM v4 program
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RaydiumAmmDecoder;
+
+impl ProgramDecoder for RaydiumAmmDecoder {
+    fn program_id(&self) -> Pubkey {
+        RAYDIUM_AMM_PROGRAM_ID
+    }
+
+    fn name(&self) -> &str {
+        "raydium-amm"
+    }
+
+    fn decode(
+        &self,
+        data: &[u8],
+        accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<DecodedInstruction> {
+        Ok(DecodedInstruction::RaydiumAmm(Box::new(
+            RaydiumAmmInstruction::decode(data, accounts)?,
+        )))
+    }
+}
+
+pub const INITIALIZE2_IX_TAG: u8 = 1;
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct Initialize2IxArgs {
+    pub nonce: u8,
+    pub open_time: u64,
+    pub init_pc_amount: u64,
+    pub init_coin_amount: u64,
+}
+
+pub const INITIALIZE2_IX_ACCOUNTS_LEN: usize = 21;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Initialize2Keys {
+    pub token_program: Pubkey,
+    pub associated_token_program: Pubkey,
+    pub system_program: Pubkey,
+    pub rent: Pubkey,
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub amm_open_orders: Pubkey,
+    pub lp_mint: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub pool_coin_token_account: Pubkey,
+    pub pool_pc_token_account: Pubkey,
+    pub pool_withdraw_queue: Pubkey,
+    pub amm_target_orders: Pubkey,
+    pub pool_temp_lp: Pubkey,
+    pub serum_program: Pubkey,
+    pub serum_market: Pubkey,
+    pub user_wallet: Pubkey,
+    pub user_token_coin: Pubkey,
+    pub user_token_pc: Pubkey,
+    pub user_lp_token_account: Pubkey,
+}
+impl From<[Pubkey; INITIALIZE2_IX_ACCOUNTS_LEN]> for Initialize2Keys {
+    fn from(pubkeys: [Pubkey; INITIALIZE2_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            token_program: pubkeys[0],
+            associated_token_program: pubkeys[1],
+            system_program: pubkeys[2],
+            rent: pubkeys[3],
+            amm: pubkeys[4],
+            amm_authority: pubkeys[5],
+            amm_open_orders: pubkeys[6],
+            lp_mint: pubkeys[7],
+            coin_mint: pubkeys[8],
+            pc_mint: pubkeys[9],
+            pool_coin_token_account: pubkeys[10],
+            pool_pc_token_account: pubkeys[11],
+            pool_withdraw_queue: pubkeys[12],
+            amm_target_orders: pubkeys[13],
+            pool_temp_lp: pubkeys[14],
+            serum_program: pubkeys[15],
+            serum_market: pubkeys[16],
+            user_wallet: pubkeys[17],
+            user_token_coin: pubkeys[18],
+            user_token_pc: pubkeys[19],
+            user_lp_token_account: pubkeys[20],
+        }
+    }
+}
+
+pub const DEPOSIT_IX_TAG: u8 = 3;
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct DepositIxArgs {
+    pub max_coin_amount: u64,
+    pub max_pc_amount: u64,
+    pub base_side: u64,
+}
+
+pub const DEPOSIT_IX_ACCOUNTS_LEN: usize = 14;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DepositKeys {
+    pub token_program: Pubkey,
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub amm_open_orders: Pubkey,
+    pub amm_target_orders: Pubkey,
+    pub lp_mint: Pubkey,
+    pub pool_coin_token_account: Pubkey,
+    pub pool_pc_token_account: Pubkey,
+    pub serum_market: Pubkey,
+    pub user_coin_token_account: Pubkey,
+    pub user_pc_token_account: Pubkey,
+    pub user_lp_token_account: Pubkey,
+    pub user_owner: Pubkey,
+    pub serum_event_queue: Pubkey,
+}
+impl From<[Pubkey; DEPOSIT_IX_ACCOUNTS_LEN]> for DepositKeys {
+    fn from(pubkeys: [Pubkey; DEPOSIT_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            token_program: pubkeys[0],
+            amm: pubkeys[1],
+            amm_authority: pubkeys[2],
+            amm_open_orders: pubkeys[3],
+            amm_target_orders: pubkeys[4],
+            lp_mint: pubkeys[5],
+            pool_coin_token_account: pubkeys[6],
+            pool_pc_token_account: pubkeys[7],
+            serum_market: pubkeys[8],
+            user_coin_token_account: pubkeys[9],
+            user_pc_token_account: pubkeys[10],
+            user_lp_token_account: pubkeys[11],
+            user_owner: pubkeys[12],
+            serum_event_queue: pubkeys[13],
+        }
+    }
+}
+
+pub const WITHDRAW_IX_TAG: u8 = 4;
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct WithdrawIxArgs {
+    pub amount: u64,
+}
+
+pub const WITHDRAW_IX_ACCOUNTS_LEN: usize = 22;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WithdrawKeys {
+    pub token_program: Pubkey,
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub amm_open_orders: Pubkey,
+    pub amm_target_orders: Pubkey,
+    pub lp_mint: Pubkey,
+    pub pool_coin_token_account: Pubkey,
+    pub pool_pc_token_account: Pubkey,
+    pub pool_withdraw_queue: Pubkey,
+    pub pool_temp_lp_token_account: Pubkey,
+    pub serum_program: Pubkey,
+    pub serum_market: Pubkey,
+    pub serum_coin_vault: Pubkey,
+    pub serum_pc_vault: Pubkey,
+    pub serum_vault_signer: Pubkey,
+    pub user_lp_token_account: Pubkey,
+    pub user_coin_token_account: Pubkey,
+    pub user_pc_token_account: Pubkey,
+    pub user_owner: Pubkey,
+    pub serum_event_queue: Pubkey,
+    pub serum_bids: Pubkey,
+    pub serum_asks: Pubkey,
+}
+impl From<[Pubkey; WITHDRAW_IX_ACCOUNTS_LEN]> for WithdrawKeys {
+    fn from(pubkeys: [Pubkey; WITHDRAW_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            token_program: pubkeys[0],
+            amm: pubkeys[1],
+            amm_authority: pubkeys[2],
+            amm_open_orders: pubkeys[3],
+            amm_target_orders: pubkeys[4],
+            lp_mint: pubkeys[5],
+            pool_coin_token_account: pubkeys[6],
+            pool_pc_token_account: pubkeys[7],
+            pool_withdraw_queue: pubkeys[8],
+            pool_temp_lp_token_account: pubkeys[9],
+            serum_program: pubkeys[10],
+            serum_market: pubkeys[11],
+            serum_coin_vault: pubkeys[12],
+            serum_pc_vault: pubkeys[13],
+            serum_vault_signer: pubkeys[14],
+            user_lp_token_account: pubkeys[15],
+            user_coin_token_account: pubkeys[16],
+            user_pc_token_account: pubkeys[17],
+            user_owner: pubkeys[18],
+            serum_event_queue: pubkeys[19],
+            serum_bids: pubkeys[20],
+            serum_asks: pubkeys[21],
+        }
+    }
+}
+
+pub const SWAP_BASE_IN_IX_TAG: u8 = 9;
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct SwapBaseInIxArgs {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+pub const SWAP_BASE_OUT_IX_TAG: u8 = 11;
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize,
+)]
+pub struct SwapBaseOutIxArgs {
+    pub max_amount_in: u64,
+    pub amount_out: u64,
+}
+
+/// Swaps take 18 accounts, or 17 when the AMM target orders account is omitted
+pub const SWAP_IX_ACCOUNTS_LEN: usize = 18;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SwapKeys {
+    pub token_program: Pubkey,
+    pub amm: Pubkey,
+    pub amm_authority: Pubkey,
+    pub amm_open_orders: Pubkey,
+    pub amm_target_orders: Option<Pubkey>,
+    pub pool_coin_token_account: Pubkey,
+    pub pool_pc_token_account: Pubkey,
+    pub serum_program: Pubkey,
+    pub serum_market: Pubkey,
+    pub serum_bids: Pubkey,
+    pub serum_asks: Pubkey,
+    pub serum_event_queue: Pubkey,
+    pub serum_coin_vault: Pubkey,
+    pub serum_pc_vault: Pubkey,
+    pub serum_vault_signer: Pubkey,
+    pub user_source_token_account: Pubkey,
+    pub user_destination_token_account: Pubkey,
+    pub user_source_owner: Pubkey,
+}
+impl SwapKeys {
+    /// Name swap accounts, telling the 17 and 18 account layouts apart by length
+    pub fn from_accounts(accounts: &[Pubkey]) -> std::io::Result<Self> {
+        if accounts.len() >= SWAP_IX_ACCOUNTS_LEN {
+            return Ok(keys_array::<SWAP_IX_ACCOUNTS_LEN>(accounts)?.into());
+        }
+        let pubkeys = keys_array::<{ SWAP_IX_ACCOUNTS_LEN - 1 }>(accounts)?;
+        Ok(Self {
+            token_program: pubkeys[0],
+            amm: pubkeys[1],
+            amm_authority: pubkeys[2],
+            amm_open_orders: pubkeys[3],
+            amm_target_orders: None,
+            pool_coin_token_account: pubkeys[4],
+            pool_pc_token_account: pubkeys[5],
+            serum_program: pubkeys[6],
+            serum_market: pubkeys[7],
+            serum_bids: pubkeys[8],
+            serum_asks: pubkeys[9],
+            serum_event_queue: pubkeys[10],
+            serum_coin_vault: pubkeys[11],
+            serum_pc_vault: pubkeys[12],
+            serum_vault_signer: pubkeys[13],
+            user_source_token_account: pubkeys[14],
+            user_destination_token_account: pubkeys[15],
+            user_source_owner: pubkeys[16],
+        })
+    }
+}
+impl From<[Pubkey; SWAP_IX_ACCOUNTS_LEN]> for SwapKeys {
+    fn from(pubkeys: [Pubkey; SWAP_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            token_program: pubkeys[0],
+            amm: pubkeys[1],
+            amm_authority: pubkeys[2],
+            amm_open_orders: pubkeys[3],
+            amm_target_orders: Some(pubkeys[4]),
+            pool_coin_token_account: pubkeys[5],
+            pool_pc_token_account: pubkeys[6],
+            serum_program: pubkeys[7],
+            serum_market: pubkeys[8],
+            serum_bids: pubkeys[9],
+            serum_asks: pubkeys[10],
+            serum_event_queue: pubkeys[11],
+            serum_coin_vault: pubkeys[12],
+            serum_pc_vault: pubkeys[13],
+            serum_vault_signer: pubkeys[14],
+            user_source_token_account: pubkeys[15],
+            user_destination_token_account: pubkeys[16],
+            user_source_owner: pubkeys[17],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(tag: u8, fields: &[&[u8]]) -> Vec<u8> {
+        let mut data = vec![tag];
+        for field in fields {
+            data.extend_from_slice(field);
+        }
+        data
+    }
+
+    fn accounts(len: usize) -> (Vec<Pubkey>, Vec<AccountMeta>) {
+        let pubkeys: Vec<Pubkey> = (0..len).map(|_| Pubkey::new_unique()).collect();
+        let metas = pubkeys
+            .iter()
+            .map(|pubkey| AccountMeta::new(*pubkey, false))
+            .collect();
+        (pubkeys, metas)
+    }
+
+    #[test]
+    fn decodes_each_instruction() {
+        let cases = [
+            (
+                data(
+                    INITIALIZE2_IX_TAG,
+                    &[
+                        &[254],
+                        &1_700_000_000u64.to_le_bytes(),
+                        &5_000_000_000u64.to_le_bytes(),
+                        &1_000_000_000_000u64.to_le_bytes(),
+                    ],
+                ),
+                RaydiumAmmIx::Initialize2(Initialize2IxArgs {
+                    nonce: 254,
+                    open_time: 1_700_000_000,
+                    init_pc_amount: 5_000_000_000,
+                    init_coin_amount: 1_000_000_000_000,
+                }),
+            ),
+            (
+                data(
+                    DEPOSIT_IX_TAG,
+                    &[
+                        &100u64.to_le_bytes(),
+                        &200u64.to_le_bytes(),
+                        &1u64.to_le_bytes(),
+                    ],
+                ),
+                RaydiumAmmIx::Deposit(DepositIxArgs {
+                    max_coin_amount: 100,
+                    max_pc_amount: 200,
+                    base_side: 1,
+                }),
+            ),
+            (
+                data(WITHDRAW_IX_TAG, &[&42u64.to_le_bytes()]),
+                RaydiumAmmIx::Withdraw(WithdrawIxArgs { amount: 42 }),
+            ),
+            (
+                data(
+                    SWAP_BASE_IN_IX_TAG,
+                    &[&1_000_000u64.to_le_bytes(), &950_000u64.to_le_bytes()],
+                ),
+                RaydiumAmmIx::SwapBaseIn(SwapBaseInIxArgs {
+                    amount_in: 1_000_000,
+                    minimum_amount_out: 950_000,
+                }),
+            ),
+            (
+                data(
+                    SWAP_BASE_OUT_IX_TAG,
+                    &[&1_050_000u64.to_le_bytes(), &1_000_000u64.to_le_bytes()],
+                ),
+                RaydiumAmmIx::SwapBaseOut(SwapBaseOutIxArgs {
+                    max_amount_in: 1_050_000,
+                    amount_out: 1_000_000,
+                }),
+            ),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(RaydiumAmmIx::deserialize(&data).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_tags_and_short_data() {
+        assert!(RaydiumAmmIx::deserialize(&[]).is_err());
+        assert!(RaydiumAmmIx::deserialize(&[2]).is_err());
+        assert!(RaydiumAmmIx::deserialize(&data(SWAP_BASE_IN_IX_TAG, &[&[0; 15]])).is_err());
+    }
+
+    #[test]
+    fn swap_accounts_with_target_orders() {
+        let (pubkeys, metas) = accounts(SWAP_IX_ACCOUNTS_LEN);
+        let swap = data(
+            SWAP_BASE_IN_IX_TAG,
+            &[&1u64.to_le_bytes(), &0u64.to_le_bytes()],
+        );
+        let ix = RaydiumAmmDecoder.decode(&swap, metas).unwrap();
+        let DecodedInstruction::RaydiumAmm(ix) = ix else {
+            panic!("expected a Raydium AMM instruction");
+        };
+        let RaydiumAmmKeys::Swap(keys) = ix.keys else {
+            panic!("expected swap keys");
+        };
+        assert_eq!(keys.amm, pubkeys[1]);
+        assert_eq!(keys.amm_target_orders, Some(pubkeys[4]));
+        assert_eq!(keys.pool_coin_token_account, pubkeys[5]);
+        assert_eq!(keys.user_source_owner, pubkeys[17]);
+    }
+
+    #[test]
+    fn swap_accounts_without_target_orders() {
+        let (pubkeys, metas) = accounts(SWAP_IX_ACCOUNTS_LEN - 1);
+        let swap = data(
+            SWAP_BASE_OUT_IX_TAG,
+            &[&1u64.to_le_bytes(), &1u64.to_le_bytes()],
+        );
+        let ix = RaydiumAmmInstruction::decode(&swap, metas).unwrap();
+        let RaydiumAmmKeys::Swap(keys) = ix.keys else {
+            panic!("expected swap keys");
+        };
+        assert_eq!(keys.amm_open_orders, pubkeys[3]);
+        assert_eq!(keys.amm_target_orders, None);
+        assert_eq!(keys.pool_coin_token_account, pubkeys[4]);
+        assert_eq!(keys.user_source_owner, pubkeys[16]);
+
+        let (_, short) = accounts(SWAP_IX_ACCOUNTS_LEN - 2);
+        assert!(RaydiumAmmInstruction::decode(&swap, short).is_err());
+    }
+
+    #[test]
+    fn names_liquidity_accounts() {
+        let (pubkeys, metas) = accounts(INITIALIZE2_IX_ACCOUNTS_LEN);
+        let init = data(INITIALIZE2_IX_TAG, &[&[0; 25]]);
+        let ix = RaydiumAmmInstruction::decode(&init, metas).unwrap();
+        let RaydiumAmmKeys::Initialize2(keys) = ix.keys else {
+            panic!("expected initialize2 keys");
+        };
+        assert_eq!(keys.coin_mint, pubkeys[8]);
+        assert_eq!(keys.pc_mint, pubkeys[9]);
+        assert_eq!(keys.user_lp_token_account, pubkeys[20]);
+
+        let (pubkeys, metas) = accounts(WITHDRAW_IX_ACCOUNTS_LEN);
+        let withdraw = data(WITHDRAW_IX_TAG, &[&1u64.to_le_bytes()]);
+        let ix = RaydiumAmmInstruction::decode(&withdraw, metas).unwrap();
+        let RaydiumAmmKeys::Withdraw(keys) = ix.keys else {
+            panic!("expected withdraw keys");
+        };
+        assert_eq!(keys.user_owner, pubkeys[18]);
+        assert_eq!(keys.serum_asks, pubkeys[21]);
+
+        let (pubkeys, metas) = accounts(DEPOSIT_IX_ACCOUNTS_LEN);
+        let deposit = data(DEPOSIT_IX_TAG, &[&[0; 24]]);
+        let ix = RaydiumAmmInstruction::decode(&deposit, metas).unwrap();
+        let RaydiumAmmKeys::Deposit(keys) = ix.keys else {
+            panic!("expected deposit keys");
+        };
+        assert_eq!(keys.user_owner, pubkeys[12]);
+        assert_eq!(keys.serum_event_queue, pubkeys[13]);
+    }
+}
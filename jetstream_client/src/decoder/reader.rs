@@ -0,0 +1,76 @@
+use solana_sdk::pubkey::Pubkey;
+
+/// Little-endian reader for instruction layouts that are not plain borsh
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self(buf)
+    }
+
+    /// Bytes not read yet
+    pub fn rest(&self) -> &'a [u8] {
+        self.0
+    }
+
+    fn take<const N: usize>(&mut self) -> std::io::Result<[u8; N]> {
+        let (bytes, rest) = self
+            .0
+            .split_first_chunk::<N>()
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?;
+        self.0 = rest;
+        Ok(*bytes)
+    }
+
+    pub fn u8(&mut self) -> std::io::Result<u8> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    pub fn u16(&mut self) -> std::io::Result<u16> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    pub fn u32(&mut self) -> std::io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub fn u64(&mut self) -> std::io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    pub fn u128(&mut self) -> std::io::Result<u128> {
+        Ok(u128::from_le_bytes(self.take()?))
+    }
+
+    /// Next `len` bytes
+    pub fn bytes(&mut self, len: usize) -> std::io::Result<&'a [u8]> {
+        if len > self.0.len() {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    pub fn pubkey(&mut self) -> std::io::Result<Pubkey> {
+        Ok(Pubkey::new_from_array(self.take()?))
+    }
+
+    /// `COption<Pubkey>` as packed by the token program: a one byte tag, then the key if set
+    pub fn option_pubkey(&mut self) -> std::io::Result<Option<Pubkey>> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => self.pubkey().map(Some),
+            tag => Err(std::io::Error::other(format!(
+                "Invalid optional pubkey tag {}",
+                tag
+            ))),
+        }
+    }
+
+    /// Bincode string: a u64 length followed by UTF-8 bytes
+    pub fn bincode_string(&mut self) -> std::io::Result<String> {
+        let len = usize::try_from(self.u64()?).map_err(std::io::Error::other)?;
+        String::from_utf8(self.bytes(len)?.to_vec()).map_err(std::io::Error::other)
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use jetstream_protos::jetstream::{CompiledInstruction, SubscribeUpdateTransactionInfo};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+use super::accounts::TransactionAccounts;
+use super::anchor_idl::AnchorInstruction;
+use super::compute_budget::{ComputeBudgetDecoder, ComputeBudgetIx};
+use super::pumpfun::{PumpFunDecoder, PumpInstruction};
+use super::raydium_amm::{RaydiumAmmDecoder, RaydiumAmmInstruction};
+use super::spl_token::{SplTokenDecoder, SplTokenInstruction};
+use super::system_program::{SystemInstruction, SystemProgramDecoder};
+
+/// Decoder for the instructions of one program
+pub trait ProgramDecoder: Send + Sync {
+    /// Program whose instructions this decoder understands
+    fn program_id(&self) -> Pubkey;
+
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// Decode instruction data invoked with `accounts`
+    fn decode(&self, data: &[u8], accounts: Vec<AccountMeta>)
+        -> anyhow::Result<DecodedInstruction>;
+}
+
+/// Instruction decoded by a [`ProgramDecoder`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodedInstruction {
+    PumpFun(Box<PumpInstruction>),
+    RaydiumAmm(Box<RaydiumAmmInstruction>),
+    SplToken(Box<SplTokenInstruction>),
+    System(Box<SystemInstruction>),
+    ComputeBudget(ComputeBudgetIx),
+    /// Instruction decoded with an Anchor IDL
+    Anchor(Box<AnchorInstruction>),
+    /// Instruction of a decoder registered outside this crate
+    Custom {
+        decoder: String,
+        value: serde_json::Value,
+        accounts: Vec<AccountMeta>,
+    },
+}
+
+impl DecodedInstruction {
+    /// Decoded arguments as JSON: the instruction enum tagged by variant name
+    /// (`{"Buy": {...}}`), `{name: args}` for Anchor instructions
+    pub fn args_json(&self) -> serde_json::Result<serde_json::Value> {
+        match self {
+            Self::PumpFun(ix) => serde_json::to_value(&ix.ix),
+            Self::RaydiumAmm(ix) => serde_json::to_value(&ix.ix),
+            Self::SplToken(ix) => serde_json::to_value(&ix.ix),
+            Self::System(ix) => serde_json::to_value(&ix.ix),
+            Self::ComputeBudget(ix) => serde_json::to_value(ix),
+            Self::Anchor(ix) => Ok(serde_json::json!({ ix.name.as_str(): ix.args })),
+            Self::Custom { value, .. } => Ok(value.clone()),
+        }
+    }
+}
+
+/// Decoded instruction with its position in the transaction
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactionInstruction {
+    pub index: usize,
+    pub program_id: Pubkey,
+    pub instruction: DecodedInstruction,
+}
+
+/// Program decoders keyed by program id
+#[derive(Clone, Default)]
+pub struct DecoderRegistry {
+    decoders: HashMap<Pubkey, Arc<dyn ProgramDecoder>>,
+}
+
+impl DecoderRegistry {
+    /// Registry without any decoders
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the decoders shipped with this crate
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(PumpFunDecoder));
+        registry.register(Arc::new(RaydiumAmmDecoder));
+        registry.register(Arc::new(SplTokenDecoder::token()));
+        registry.register(Arc::new(SplTokenDecoder::token_2022()));
+        registry.register(Arc::new(SystemProgramDecoder));
+        registry.register(Arc::new(ComputeBudgetDecoder));
+        registry
+    }
+
+    /// Register `decoder`, replacing any decoder of the same program
+    pub fn register(&mut self, decoder: Arc<dyn ProgramDecoder>) -> &mut Self {
+        self.decoders.insert(decoder.program_id(), decoder);
+        self
+    }
+
+    pub fn get(&self, program_id: &Pubkey) -> Option<&Arc<dyn ProgramDecoder>> {
+        self.decoders.get(program_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.decoders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.decoders.is_empty()
+    }
+
+    /// Decode `instruction` with the decoder of its program, `None` if there is none
+    pub fn decode(
+        &self,
+        accounts: &TransactionAccounts,
+        instruction: &CompiledInstruction,
+    ) -> anyhow::Result<Option<DecodedInstruction>> {
+        let Some(decoder) = accounts
+            .program_id(instruction)
+            .and_then(|program_id| self.decoders.get(program_id))
+        else {
+            return Ok(None);
+        };
+
+        let metas = accounts.account_metas(instruction)?;
+        decoder
+            .decode(&instruction.data, metas)
+            .map(Some)
+            .map_err(|e| e.context(format!("Failed to decode {} instruction", decoder.name())))
+    }
+
+    /// Decode the top-level instructions of a transaction, logging failures at debug level
+    pub fn decode_transaction(
+        &self,
+        tx_info: &SubscribeUpdateTransactionInfo,
+        accounts: &TransactionAccounts,
+    ) -> Vec<TransactionInstruction> {
+        let mut decoded = Vec::new();
+        for (index, instruction) in tx_info.instructions.iter().enumerate() {
+            match self.decode(accounts, instruction) {
+                Ok(Some(ix)) => decoded.push(TransactionInstruction {
+                    index,
+                    program_id: *accounts
+                        .program_id(instruction)
+                        .expect("decoded instruction has a program"),
+                    instruction: ix,
+                }),
+                Ok(None) => {}
+                Err(e) => log::debug!("Instruction #{}: {:#}", index, e),
+            }
+        }
+        decoded
+    }
+}
+
+impl std::fmt::Debug for DecoderRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.decoders
+                    .iter()
+                    .map(|(program_id, decoder)| (program_id, decoder.name())),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::pumpfun::PUMPFUN_PROGRAM_ID;
+    use solana_sdk::message::v0::LoadedAddresses;
+
+    /// Custom decoder echoing the instruction data, failing on empty data
+    struct EchoDecoder(Pubkey);
+
+    impl ProgramDecoder for EchoDecoder {
+        fn program_id(&self) -> Pubkey {
+            self.0
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn decode(
+            &self,
+            data: &[u8],
+            accounts: Vec<AccountMeta>,
+        ) -> anyhow::Result<DecodedInstruction> {
+            anyhow::ensure!(!data.is_empty(), "empty instruction data");
+            Ok(DecodedInstruction::Custom {
+                decoder: self.name().to_string(),
+                value: serde_json::json!({ "data": data }),
+                accounts,
+            })
+        }
+    }
+
+    fn instruction(program_id_index: u32, accounts: &[u8], data: &[u8]) -> CompiledInstruction {
+        CompiledInstruction {
+            program_id_index,
+            accounts: accounts.to_vec(),
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn custom_decoders_are_dispatched_by_program() {
+        let payer = Pubkey::new_unique();
+        let echo = Pubkey::new_unique();
+        let unregistered = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let tx_info = SubscribeUpdateTransactionInfo {
+            account_keys: [payer, account, echo, unregistered]
+                .iter()
+                .map(|key| key.to_bytes().to_vec())
+                .collect(),
+            num_required_signatures: 1,
+            num_readonly_unsigned_accounts: 2,
+            instructions: vec![
+                instruction(2, &[0, 1], &[1, 2]),
+                instruction(3, &[0], &[9]),
+                // Rejected by the decoder, logged and skipped
+                instruction(2, &[0], &[]),
+                // Program index out of range
+                instruction(9, &[0], &[1]),
+                instruction(2, &[1], &[3]),
+            ],
+            ..Default::default()
+        };
+        let accounts = TransactionAccounts::new(&tx_info, LoadedAddresses::default()).unwrap();
+
+        let mut registry = DecoderRegistry::new();
+        registry.register(Arc::new(EchoDecoder(echo)));
+        assert_eq!(registry.len(), 1);
+
+        let decoded = registry.decode_transaction(&tx_info, &accounts);
+        assert_eq!(
+            decoded.iter().map(|ix| ix.index).collect::<Vec<_>>(),
+            [0, 4]
+        );
+        assert!(decoded.iter().all(|ix| ix.program_id == echo));
+        assert_eq!(
+            decoded[0].instruction,
+            DecodedInstruction::Custom {
+                decoder: "echo".to_string(),
+                value: serde_json::json!({ "data": [1, 2] }),
+                accounts: vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(account, false)
+                ],
+            }
+        );
+        assert_eq!(
+            decoded[1].instruction.args_json().unwrap(),
+            serde_json::json!({ "data": [3] })
+        );
+
+        assert!(registry
+            .decode(&accounts, &tx_info.instructions[2])
+            .unwrap_err()
+            .to_string()
+            .contains("echo"));
+        assert_eq!(
+            registry
+                .decode(&accounts, &tx_info.instructions[1])
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn register_replaces_the_decoder_of_a_program() {
+        let mut registry = DecoderRegistry::with_defaults();
+        let defaults = registry.len();
+        registry.register(Arc::new(EchoDecoder(PUMPFUN_PROGRAM_ID)));
+        assert_eq!(registry.len(), defaults);
+        assert_eq!(registry.get(&PUMPFUN_PROGRAM_ID).unwrap().name(), "echo");
+    }
+}
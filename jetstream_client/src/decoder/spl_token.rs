@@ -0,0 +1,1016 @@
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use super::accounts::keys_array;
+use super::reader::Reader;
+use super::registry::{DecodedInstruction, ProgramDecoder};
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Instructions of the SPL Token program and the Token-2022 additions
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub enum TokenIx {
+    InitializeMint {
+        decimals: u8,
+        mint_authority: Pubkey,
+        freeze_authority: Option<Pubkey>,
+    },
+    InitializeAccount,
+    InitializeMultisig {
+        m: u8,
+    },
+    Transfer {
+        amount: u64,
+    },
+    Approve {
+        amount: u64,
+    },
+    Revoke,
+    SetAuthority {
+        authority_type: u8,
+        new_authority: Option<Pubkey>,
+    },
+    MintTo {
+        amount: u64,
+    },
+    Burn {
+        amount: u64,
+    },
+    CloseAccount,
+    FreezeAccount,
+    ThawAccount,
+    TransferChecked {
+        amount: u64,
+        decimals: u8,
+    },
+    ApproveChecked {
+        amount: u64,
+        decimals: u8,
+    },
+    MintToChecked {
+        amount: u64,
+        decimals: u8,
+    },
+    BurnChecked {
+        amount: u64,
+        decimals: u8,
+    },
+    InitializeAccount2 {
+        owner: Pubkey,
+    },
+    SyncNative,
+    InitializeAccount3 {
+        owner: Pubkey,
+    },
+    InitializeMultisig2 {
+        m: u8,
+    },
+    InitializeMint2 {
+        decimals: u8,
+        mint_authority: Pubkey,
+        freeze_authority: Option<Pubkey>,
+    },
+    GetAccountDataSize,
+    InitializeImmutableOwner,
+    AmountToUiAmount {
+        amount: u64,
+    },
+    UiAmountToAmount {
+        ui_amount: String,
+    },
+    InitializeMintCloseAuthority {
+        close_authority: Option<Pubkey>,
+    },
+    TransferFee(TransferFeeIx),
+    Reallocate,
+    CreateNativeMint,
+    InitializeNonTransferableMint,
+    InitializePermanentDelegate {
+        delegate: Pubkey,
+    },
+    WithdrawExcessLamports,
+    /// Token-2022 extension instruction whose payload is not decoded
+    Extension {
+        extension: TokenExtension,
+        data: Vec<u8>,
+    },
+}
+
+/// Token-2022 extensions whose instructions are prefixed by a sub-instruction tag
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub enum TokenExtension {
+    ConfidentialTransfer,
+    DefaultAccountState,
+    MemoTransfer,
+    InterestBearingMint,
+    CpiGuard,
+    TransferHook,
+    ConfidentialTransferFee,
+    MetadataPointer,
+    GroupPointer,
+    GroupMemberPointer,
+    ConfidentialMintBurn,
+    ScaledUiAmount,
+    Pausable,
+}
+
+/// Sub-instructions of the Token-2022 transfer fee extension
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub enum TransferFeeIx {
+    InitializeTransferFeeConfig {
+        transfer_fee_config_authority: Option<Pubkey>,
+        withdraw_withheld_authority: Option<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+    TransferCheckedWithFee {
+        amount: u64,
+        decimals: u8,
+        fee: u64,
+    },
+    WithdrawWithheldTokensFromMint,
+    WithdrawWithheldTokensFromAccounts {
+        num_token_accounts: u8,
+    },
+    HarvestWithheldTokensToMint,
+    SetTransferFee {
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+}
+
+/// First instruction tag that only exists in Token-2022
+const FIRST_TOKEN_2022_TAG: u8 = 25;
+
+impl TokenIx {
+    /// Decode instruction data sent to `program_id`
+    pub fn deserialize(program_id: &Pubkey, buf: &[u8]) -> std::io::Result<Self> {
+        if *program_id != TOKEN_PROGRAM_ID && *program_id != TOKEN_2022_PROGRAM_ID {
+            return Err(std::io::Error::other(
+                "Not an SPL Token program instruction",
+            ));
+        }
+
+        let mut reader = Reader::new(buf);
+        let tag = reader.u8()?;
+        if tag >= FIRST_TOKEN_2022_TAG && *program_id != TOKEN_2022_PROGRAM_ID {
+            return Err(std::io::Error::other(format!(
+                "Token instruction tag {} is only valid for Token-2022",
+                tag
+            )));
+        }
+
+        let ix = match tag {
+            0 => Self::InitializeMint {
+                decimals: reader.u8()?,
+                mint_authority: reader.pubkey()?,
+                freeze_authority: reader.option_pubkey()?,
+            },
+            1 => Self::InitializeAccount,
+            2 => Self::InitializeMultisig { m: reader.u8()? },
+            3 => Self::Transfer {
+                amount: reader.u64()?,
+            },
+            4 => Self::Approve {
+                amount: reader.u64()?,
+            },
+            5 => Self::Revoke,
+            6 => Self::SetAuthority {
+                authority_type: reader.u8()?,
+                new_authority: reader.option_pubkey()?,
+            },
+            7 => Self::MintTo {
+                amount: reader.u64()?,
+            },
+            8 => Self::Burn {
+                amount: reader.u64()?,
+            },
+            9 => Self::CloseAccount,
+            10 => Self::FreezeAccount,
+            11 => Self::ThawAccount,
+            12 => Self::TransferChecked {
+                amount: reader.u64()?,
+                decimals: reader.u8()?,
+            },
+            13 => Self::ApproveChecked {
+                amount: reader.u64()?,
+                decimals: reader.u8()?,
+            },
+            14 => Self::MintToChecked {
+                amount: reader.u64()?,
+                decimals: reader.u8()?,
+            },
+            15 => Self::BurnChecked {
+                amount: reader.u64()?,
+                decimals: reader.u8()?,
+            },
+            16 => Self::InitializeAccount2 {
+                owner: reader.pubkey()?,
+            },
+            17 => Self::SyncNative,
+            18 => Self::InitializeAccount3 {
+                owner: reader.pubkey()?,
+            },
+            19 => Self::InitializeMultisig2 { m: reader.u8()? },
+            20 => Self::InitializeMint2 {
+                decimals: reader.u8()?,
+                mint_authority: reader.pubkey()?,
+                freeze_authority: reader.option_pubkey()?,
+            },
+            21 => Self::GetAccountDataSize,
+            22 => Self::InitializeImmutableOwner,
+            23 => Self::AmountToUiAmount {
+                amount: reader.u64()?,
+            },
+            24 => Self::UiAmountToAmount {
+                ui_amount: String::from_utf8(reader.rest().to_vec())
+                    .map_err(std::io::Error::other)?,
+            },
+            25 => Self::InitializeMintCloseAuthority {
+                close_authority: reader.option_pubkey()?,
+            },
+            26 => Self::TransferFee(TransferFeeIx::deserialize(&mut reader)?),
+            27 => Self::extension(TokenExtension::ConfidentialTransfer, reader),
+            28 => Self::extension(TokenExtension::DefaultAccountState, reader),
+            29 => Self::Reallocate,
+            30 => Self::extension(TokenExtension::MemoTransfer, reader),
+            31 => Self::CreateNativeMint,
+            32 => Self::InitializeNonTransferableMint,
+            33 => Self::extension(TokenExtension::InterestBearingMint, reader),
+            34 => Self::extension(TokenExtension::CpiGuard, reader),
+            35 => Self::InitializePermanentDelegate {
+                delegate: reader.pubkey()?,
+            },
+            36 => Self::extension(TokenExtension::TransferHook, reader),
+            37 => Self::extension(TokenExtension::ConfidentialTransferFee, reader),
+            38 => Self::WithdrawExcessLamports,
+            39 => Self::extension(TokenExtension::MetadataPointer, reader),
+            40 => Self::extension(TokenExtension::GroupPointer, reader),
+            41 => Self::extension(TokenExtension::GroupMemberPointer, reader),
+            42 => Self::extension(TokenExtension::ConfidentialMintBurn, reader),
+            43 => Self::extension(TokenExtension::ScaledUiAmount, reader),
+            44 => Self::extension(TokenExtension::Pausable, reader),
+            _ => {
+                return Err(std::io::Error::other(format!(
+                    "Token instruction tag {} not supported",
+                    tag
+                )))
+            }
+        };
+        Ok(ix)
+    }
+
+    fn extension(extension: TokenExtension, reader: Reader) -> Self {
+        Self::Extension {
+            extension,
+            data: reader.rest().to_vec(),
+        }
+    }
+}
+
+impl TransferFeeIx {
+    fn deserialize(reader: &mut Reader) -> std::io::Result<Self> {
+        let tag = reader.u8()?;
+        let ix = match tag {
+            0 => Self::InitializeTransferFeeConfig {
+                transfer_fee_config_authority: reader.option_pubkey()?,
+                withdraw_withheld_authority: reader.option_pubkey()?,
+                transfer_fee_basis_points: reader.u16()?,
+                maximum_fee: reader.u64()?,
+            },
+            1 => Self::TransferCheckedWithFee {
+                amount: reader.u64()?,
+                decimals: reader.u8()?,
+                fee: reader.u64()?,
+            },
+            2 => Self::WithdrawWithheldTokensFromMint,
+            3 => Self::WithdrawWithheldTokensFromAccounts {
+                num_token_accounts: reader.u8()?,
+            },
+            4 => Self::HarvestWithheldTokensToMint,
+            5 => Self::SetTransferFee {
+                transfer_fee_basis_points: reader.u16()?,
+                maximum_fee: reader.u64()?,
+            },
+            _ => {
+                return Err(std::io::Error::other(format!(
+                    "Transfer fee instruction tag {} not supported",
+                    tag
+                )))
+            }
+        };
+        Ok(ix)
+    }
+}
+
+/// Decoded token instruction with the accounts it was invoked with
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplTokenInstruction {
+    pub ix: TokenIx,
+    pub keys: TokenKeys,
+    pub accounts: Vec<AccountMeta>,
+}
+
+impl SplTokenInstruction {
+    /// Decode instruction data sent to `program_id` and invoked with `accounts`
+    pub fn decode(
+        program_id: &Pubkey,
+        data: &[u8],
+        accounts: Vec<AccountMeta>,
+    ) -> std::io::Result<Self> {
+        let ix = TokenIx::deserialize(program_id, data)?;
+        let pubkeys: Vec<Pubkey> = accounts.iter().map(|meta| meta.pubkey).collect();
+        let keys = TokenKeys::from_accounts(&ix, &pubkeys)?;
+        Ok(Self { ix, keys, accounts })
+    }
+
+    /// Tokens moved, minted or burned by this instruction
+    pub fn flow(&self) -> Option<TokenFlow> {
+        let flow = match (&self.ix, &self.keys) {
+            (TokenIx::Transfer { amount }, TokenKeys::Transfer(keys)) => TokenFlow {
+                source: Some(keys.source),
+                destination: Some(keys.destination),
+                mint: None,
+                authority: keys.authority,
+                amount: *amount,
+                decimals: None,
+                fee: None,
+            },
+            (TokenIx::TransferChecked { amount, decimals }, TokenKeys::TransferChecked(keys)) => {
+                TokenFlow {
+                    source: Some(keys.source),
+                    destination: Some(keys.destination),
+                    mint: Some(keys.mint),
+                    authority: keys.authority,
+                    amount: *amount,
+                    decimals: Some(*decimals),
+                    fee: None,
+                }
+            }
+            (
+                TokenIx::TransferFee(TransferFeeIx::TransferCheckedWithFee {
+                    amount,
+                    decimals,
+                    fee,
+                }),
+                TokenKeys::TransferChecked(keys),
+            ) => TokenFlow {
+                source: Some(keys.source),
+                destination: Some(keys.destination),
+                mint: Some(keys.mint),
+                authority: keys.authority,
+                amount: *amount,
+                decimals: Some(*decimals),
+                fee: Some(*fee),
+            },
+            (TokenIx::MintTo { amount }, TokenKeys::MintTo(keys)) => TokenFlow {
+                source: None,
+                destination: Some(keys.account),
+                mint: Some(keys.mint),
+                authority: keys.authority,
+                amount: *amount,
+                decimals: None,
+                fee: None,
+            },
+            (TokenIx::MintToChecked { amount, decimals }, TokenKeys::MintTo(keys)) => TokenFlow {
+                source: None,
+                destination: Some(keys.account),
+                mint: Some(keys.mint),
+                authority: keys.authority,
+                amount: *amount,
+                decimals: Some(*decimals),
+                fee: None,
+            },
+            (TokenIx::Burn { amount }, TokenKeys::Burn(keys)) => TokenFlow {
+                source: Some(keys.account),
+                destination: None,
+                mint: Some(keys.mint),
+                authority: keys.authority,
+                amount: *amount,
+                decimals: None,
+                fee: None,
+            },
+            (TokenIx::BurnChecked { amount, decimals }, TokenKeys::Burn(keys)) => TokenFlow {
+                source: Some(keys.account),
+                destination: None,
+                mint: Some(keys.mint),
+                authority: keys.authority,
+                amount: *amount,
+                decimals: Some(*decimals),
+                fee: None,
+            },
+            _ => return None,
+        };
+        Some(flow)
+    }
+}
+
+/// Token movement: a transfer has both sides, a mint no source and a burn no destination
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenFlow {
+    pub source: Option<Pubkey>,
+    pub destination: Option<Pubkey>,
+    /// Only known for checked instructions, mints and burns
+    pub mint: Option<Pubkey>,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub decimals: Option<u8>,
+    /// Transfer fee withheld by Token-2022
+    pub fee: Option<u64>,
+}
+
+/// Named accounts of a token instruction
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKeys {
+    InitializeMint(InitializeMintKeys),
+    InitializeAccount(InitializeAccountKeys),
+    Transfer(TransferKeys),
+    TransferChecked(TransferCheckedKeys),
+    MintTo(MintToKeys),
+    Burn(BurnKeys),
+    CloseAccount(CloseAccountKeys),
+    /// Instruction without named accounts; see `SplTokenInstruction::accounts`
+    Unnamed,
+}
+
+impl TokenKeys {
+    /// Name the `accounts` of `ix`; fails if there are fewer than the instruction expects
+    pub fn from_accounts(ix: &TokenIx, accounts: &[Pubkey]) -> std::io::Result<Self> {
+        match ix {
+            TokenIx::InitializeMint { .. } => {
+                Ok(Self::InitializeMint(keys_array(accounts)?.into()))
+            }
+            TokenIx::InitializeMint2 { .. } => Ok(Self::InitializeMint(InitializeMintKeys {
+                mint: keys_array::<1>(accounts)?[0],
+            })),
+            TokenIx::InitializeAccount => Ok(Self::InitializeAccount(keys_array(accounts)?.into())),
+            TokenIx::InitializeAccount2 { owner } | TokenIx::InitializeAccount3 { owner } => {
+                let [account, mint] = keys_array(accounts)?;
+                Ok(Self::InitializeAccount(InitializeAccountKeys {
+                    account,
+                    mint,
+                    owner: *owner,
+                }))
+            }
+            TokenIx::Transfer { .. } => Ok(Self::Transfer(keys_array(accounts)?.into())),
+            TokenIx::TransferChecked { .. }
+            | TokenIx::TransferFee(TransferFeeIx::TransferCheckedWithFee { .. }) => {
+                Ok(Self::TransferChecked(keys_array(accounts)?.into()))
+            }
+            TokenIx::MintTo { .. } | TokenIx::MintToChecked { .. } => {
+                Ok(Self::MintTo(keys_array(accounts)?.into()))
+            }
+            TokenIx::Burn { .. } | TokenIx::BurnChecked { .. } => {
+                Ok(Self::Burn(keys_array(accounts)?.into()))
+            }
+            TokenIx::CloseAccount => Ok(Self::CloseAccount(keys_array(accounts)?.into())),
+            _ => Ok(Self::Unnamed),
+        }
+    }
+}
+
+pub const INITIALIZE_MINT_IX_ACCOUNTS_LEN: usize = 2;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InitializeMintKeys {
+    pub mint: Pubkey,
+}
+impl From<[Pubkey; INITIALIZE_MINT_IX_ACCOUNTS_LEN]> for InitializeMintKeys {
+    fn from(pubkeys: [Pubkey; INITIALIZE_MINT_IX_ACCOUNTS_LEN]) -> Self {
+        Self { mint: pubkeys[0] }
+    }
+}
+
+pub const INITIALIZE_ACCOUNT_IX_ACCOUNTS_LEN: usize = 4;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InitializeAccountKeys {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+}
+impl From<[Pubkey; INITIALIZE_ACCOUNT_IX_ACCOUNTS_LEN]> for InitializeAccountKeys {
+    fn from(pubkeys: [Pubkey; INITIALIZE_ACCOUNT_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            account: pubkeys[0],
+            mint: pubkeys[1],
+            owner: pubkeys[2],
+        }
+    }
+}
+
+pub const TRANSFER_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TransferKeys {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+}
+impl From<[Pubkey; TRANSFER_IX_ACCOUNTS_LEN]> for TransferKeys {
+    fn from(pubkeys: [Pubkey; TRANSFER_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            source: pubkeys[0],
+            destination: pubkeys[1],
+            authority: pubkeys[2],
+        }
+    }
+}
+
+pub const TRANSFER_CHECKED_IX_ACCOUNTS_LEN: usize = 4;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TransferCheckedKeys {
+    pub source: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+}
+impl From<[Pubkey; TRANSFER_CHECKED_IX_ACCOUNTS_LEN]> for TransferCheckedKeys {
+    fn from(pubkeys: [Pubkey; TRANSFER_CHECKED_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            source: pubkeys[0],
+            mint: pubkeys[1],
+            destination: pubkeys[2],
+            authority: pubkeys[3],
+        }
+    }
+}
+
+pub const MINT_TO_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MintToKeys {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub authority: Pubkey,
+}
+impl From<[Pubkey; MINT_TO_IX_ACCOUNTS_LEN]> for MintToKeys {
+    fn from(pubkeys: [Pubkey; MINT_TO_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            mint: pubkeys[0],
+            account: pubkeys[1],
+            authority: pubkeys[2],
+        }
+    }
+}
+
+pub const BURN_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BurnKeys {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+}
+impl From<[Pubkey; BURN_IX_ACCOUNTS_LEN]> for BurnKeys {
+    fn from(pubkeys: [Pubkey; BURN_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            account: pubkeys[0],
+            mint: pubkeys[1],
+            authority: pubkeys[2],
+        }
+    }
+}
+
+pub const CLOSE_ACCOUNT_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CloseAccountKeys {
+    pub account: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+}
+impl From<[Pubkey; CLOSE_ACCOUNT_IX_ACCOUNTS_LEN]> for CloseAccountKeys {
+    fn from(pubkeys: [Pubkey; CLOSE_ACCOUNT_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            account: pubkeys[0],
+            destination: pubkeys[1],
+            authority: pubkeys[2],
+        }
+    }
+}
+
+/// [`ProgramDecoder`] for the SPL Token or the Token-2022 program
+#[derive(Clone, Copy, Debug)]
+pub struct SplTokenDecoder {
+    program_id: Pubkey,
+}
+
+impl SplTokenDecoder {
+    pub fn token() -> Self {
+        Self {
+            program_id: TOKEN_PROGRAM_ID,
+        }
+    }
+
+    pub fn token_2022() -> Self {
+        Self {
+            program_id: TOKEN_2022_PROGRAM_ID,
+        }
+    }
+}
+
+impl ProgramDecoder for SplTokenDecoder {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn name(&self) -> &str {
+        if self.program_id == TOKEN_2022_PROGRAM_ID {
+            "token-2022"
+        } else {
+            "spl-token"
+        }
+    }
+
+    fn decode(
+        &self,
+        data: &[u8],
+        accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<DecodedInstruction> {
+        Ok(DecodedInstruction::SplToken(Box::new(
+            SplTokenInstruction::decode(&self.program_id, data, accounts)?,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(tag: u8, fields: &[&[u8]]) -> Vec<u8> {
+        let mut data = vec![tag];
+        for field in fields {
+            data.extend_from_slice(field);
+        }
+        data
+    }
+
+    fn decode(program_id: &Pubkey, data: &[u8], pubkeys: &[Pubkey]) -> SplTokenInstruction {
+        let accounts = pubkeys
+            .iter()
+            .map(|pubkey| AccountMeta::new(*pubkey, false))
+            .collect();
+        SplTokenInstruction::decode(program_id, data, accounts).unwrap()
+    }
+
+    fn keys(len: usize) -> Vec<Pubkey> {
+        (0..len).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn decodes_token_instructions() {
+        let authority = Pubkey::new_unique();
+        let cases = [
+            (
+                data(0, &[&[6], authority.as_ref(), &[1], authority.as_ref()]),
+                TokenIx::InitializeMint {
+                    decimals: 6,
+                    mint_authority: authority,
+                    freeze_authority: Some(authority),
+                },
+            ),
+            (
+                data(20, &[&[9], authority.as_ref(), &[0]]),
+                TokenIx::InitializeMint2 {
+                    decimals: 9,
+                    mint_authority: authority,
+                    freeze_authority: None,
+                },
+            ),
+            (data(2, &[&[2]]), TokenIx::InitializeMultisig { m: 2 }),
+            (
+                data(3, &[&1_500u64.to_le_bytes()]),
+                TokenIx::Transfer { amount: 1_500 },
+            ),
+            (
+                data(6, &[&[2], &[1], authority.as_ref()]),
+                TokenIx::SetAuthority {
+                    authority_type: 2,
+                    new_authority: Some(authority),
+                },
+            ),
+            (
+                data(12, &[&1_500u64.to_le_bytes(), &[6]]),
+                TokenIx::TransferChecked {
+                    amount: 1_500,
+                    decimals: 6,
+                },
+            ),
+            (
+                data(15, &[&7u64.to_le_bytes(), &[0]]),
+                TokenIx::BurnChecked {
+                    amount: 7,
+                    decimals: 0,
+                },
+            ),
+            (
+                data(18, &[authority.as_ref()]),
+                TokenIx::InitializeAccount3 { owner: authority },
+            ),
+            (data(17, &[]), TokenIx::SyncNative),
+            (
+                data(24, &[b"1.25"]),
+                TokenIx::UiAmountToAmount {
+                    ui_amount: "1.25".to_string(),
+                },
+            ),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(
+                TokenIx::deserialize(&TOKEN_PROGRAM_ID, &data).unwrap(),
+                expected
+            );
+            assert_eq!(
+                TokenIx::deserialize(&TOKEN_2022_PROGRAM_ID, &data).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_data() {
+        assert!(TokenIx::deserialize(&Pubkey::new_unique(), &[3; 9]).is_err());
+        assert!(TokenIx::deserialize(&TOKEN_PROGRAM_ID, &[]).is_err());
+        assert!(TokenIx::deserialize(&TOKEN_PROGRAM_ID, &[3, 1, 2]).is_err());
+        assert!(TokenIx::deserialize(&TOKEN_PROGRAM_ID, &data(6, &[&[0], &[2]])).is_err());
+        assert!(TokenIx::deserialize(&TOKEN_2022_PROGRAM_ID, &[45]).is_err());
+    }
+
+    #[test]
+    fn decodes_token_2022_tags() {
+        let key = Pubkey::new_unique();
+        let cases = [
+            (
+                data(25, &[&[1], key.as_ref()]),
+                TokenIx::InitializeMintCloseAuthority {
+                    close_authority: Some(key),
+                },
+            ),
+            (
+                data(26, &[&[4]]),
+                TokenIx::TransferFee(TransferFeeIx::HarvestWithheldTokensToMint),
+            ),
+            (
+                data(27, &[&[5, 1, 2]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::ConfidentialTransfer,
+                    data: vec![5, 1, 2],
+                },
+            ),
+            (
+                data(28, &[&[1, 2]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::DefaultAccountState,
+                    data: vec![1, 2],
+                },
+            ),
+            (data(29, &[&[7, 0]]), TokenIx::Reallocate),
+            (
+                data(30, &[&[0]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::MemoTransfer,
+                    data: vec![0],
+                },
+            ),
+            (data(31, &[]), TokenIx::CreateNativeMint),
+            (data(32, &[]), TokenIx::InitializeNonTransferableMint),
+            (
+                data(33, &[&[1, 0xf4, 0x01]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::InterestBearingMint,
+                    data: vec![1, 0xf4, 0x01],
+                },
+            ),
+            (
+                data(34, &[&[0]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::CpiGuard,
+                    data: vec![0],
+                },
+            ),
+            (
+                data(35, &[key.as_ref()]),
+                TokenIx::InitializePermanentDelegate { delegate: key },
+            ),
+            (
+                data(36, &[&[0]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::TransferHook,
+                    data: vec![0],
+                },
+            ),
+            (
+                data(37, &[&[2]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::ConfidentialTransferFee,
+                    data: vec![2],
+                },
+            ),
+            (data(38, &[]), TokenIx::WithdrawExcessLamports),
+            (
+                data(39, &[&[0]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::MetadataPointer,
+                    data: vec![0],
+                },
+            ),
+            (
+                data(40, &[&[1]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::GroupPointer,
+                    data: vec![1],
+                },
+            ),
+            (
+                data(41, &[]),
+                TokenIx::Extension {
+                    extension: TokenExtension::GroupMemberPointer,
+                    data: vec![],
+                },
+            ),
+            (
+                data(42, &[&[3]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::ConfidentialMintBurn,
+                    data: vec![3],
+                },
+            ),
+            (
+                data(43, &[&[0]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::ScaledUiAmount,
+                    data: vec![0],
+                },
+            ),
+            (
+                data(44, &[&[1]]),
+                TokenIx::Extension {
+                    extension: TokenExtension::Pausable,
+                    data: vec![1],
+                },
+            ),
+        ];
+        assert_eq!(cases.len(), 20);
+        for (data, expected) in cases {
+            assert_eq!(
+                TokenIx::deserialize(&TOKEN_2022_PROGRAM_ID, &data).unwrap(),
+                expected
+            );
+            let err = TokenIx::deserialize(&TOKEN_PROGRAM_ID, &data).unwrap_err();
+            assert!(
+                err.to_string().contains("only valid for Token-2022"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn decodes_transfer_fee_instructions() {
+        let authority = Pubkey::new_unique();
+        let cases = [
+            (
+                data(
+                    26,
+                    &[
+                        &[0, 1],
+                        authority.as_ref(),
+                        &[0],
+                        &250u16.to_le_bytes(),
+                        &5_000u64.to_le_bytes(),
+                    ],
+                ),
+                TransferFeeIx::InitializeTransferFeeConfig {
+                    transfer_fee_config_authority: Some(authority),
+                    withdraw_withheld_authority: None,
+                    transfer_fee_basis_points: 250,
+                    maximum_fee: 5_000,
+                },
+            ),
+            (
+                data(
+                    26,
+                    &[&[1], &10_000u64.to_le_bytes(), &[6], &25u64.to_le_bytes()],
+                ),
+                TransferFeeIx::TransferCheckedWithFee {
+                    amount: 10_000,
+                    decimals: 6,
+                    fee: 25,
+                },
+            ),
+            (
+                data(26, &[&[2]]),
+                TransferFeeIx::WithdrawWithheldTokensFromMint,
+            ),
+            (
+                data(26, &[&[3, 4]]),
+                TransferFeeIx::WithdrawWithheldTokensFromAccounts {
+                    num_token_accounts: 4,
+                },
+            ),
+            (
+                data(26, &[&[5], &100u16.to_le_bytes(), &9u64.to_le_bytes()]),
+                TransferFeeIx::SetTransferFee {
+                    transfer_fee_basis_points: 100,
+                    maximum_fee: 9,
+                },
+            ),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(
+                TokenIx::deserialize(&TOKEN_2022_PROGRAM_ID, &data).unwrap(),
+                TokenIx::TransferFee(expected)
+            );
+        }
+        assert!(TokenIx::deserialize(&TOKEN_2022_PROGRAM_ID, &[26, 6]).is_err());
+    }
+
+    #[test]
+    fn transfer_flows() {
+        let pubkeys = keys(3);
+        let ix = decode(
+            &TOKEN_PROGRAM_ID,
+            &data(3, &[&42u64.to_le_bytes()]),
+            &pubkeys,
+        );
+        assert_eq!(
+            ix.flow(),
+            Some(TokenFlow {
+                source: Some(pubkeys[0]),
+                destination: Some(pubkeys[1]),
+                mint: None,
+                authority: pubkeys[2],
+                amount: 42,
+                decimals: None,
+                fee: None,
+            })
+        );
+
+        let pubkeys = keys(4);
+        let ix = decode(
+            &TOKEN_2022_PROGRAM_ID,
+            &data(
+                26,
+                &[&[1], &1_000u64.to_le_bytes(), &[6], &3u64.to_le_bytes()],
+            ),
+            &pubkeys,
+        );
+        assert_eq!(
+            ix.flow(),
+            Some(TokenFlow {
+                source: Some(pubkeys[0]),
+                destination: Some(pubkeys[2]),
+                mint: Some(pubkeys[1]),
+                authority: pubkeys[3],
+                amount: 1_000,
+                decimals: Some(6),
+                fee: Some(3),
+            })
+        );
+    }
+
+    #[test]
+    fn mint_and_burn_flows() {
+        let pubkeys = keys(3);
+        let ix = decode(
+            &TOKEN_PROGRAM_ID,
+            &data(14, &[&5u64.to_le_bytes(), &[2]]),
+            &pubkeys,
+        );
+        let flow = ix.flow().unwrap();
+        assert_eq!(flow.source, None);
+        assert_eq!(flow.destination, Some(pubkeys[1]));
+        assert_eq!(flow.mint, Some(pubkeys[0]));
+        assert_eq!(flow.decimals, Some(2));
+
+        let ix = decode(
+            &TOKEN_PROGRAM_ID,
+            &data(8, &[&5u64.to_le_bytes()]),
+            &pubkeys,
+        );
+        let flow = ix.flow().unwrap();
+        assert_eq!(flow.source, Some(pubkeys[0]));
+        assert_eq!(flow.destination, None);
+        assert_eq!(flow.mint, Some(pubkeys[1]));
+
+        let ix = decode(&TOKEN_PROGRAM_ID, &data(9, &[]), &pubkeys);
+        assert_eq!(ix.flow(), None);
+    }
+
+    #[test]
+    fn names_initialize_accounts() {
+        let owner = Pubkey::new_unique();
+        let pubkeys = keys(2);
+        let ix = decode(&TOKEN_PROGRAM_ID, &data(18, &[owner.as_ref()]), &pubkeys);
+        assert_eq!(
+            ix.keys,
+            TokenKeys::InitializeAccount(InitializeAccountKeys {
+                account: pubkeys[0],
+                mint: pubkeys[1],
+                owner,
+            })
+        );
+
+        let accounts = vec![AccountMeta::new(pubkeys[0], false)];
+        assert!(
+            SplTokenInstruction::decode(&TOKEN_PROGRAM_ID, &data(3, &[&[0; 8]]), accounts).is_err()
+        );
+    }
+}
@@ -0,0 +1,394 @@
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+use super::accounts::keys_array;
+use super::reader::Reader;
+use super::registry::{DecodedInstruction, ProgramDecoder};
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// System program instructions, bincode encoded with a u32 tag
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub enum SystemIx {
+    CreateAccount {
+        lamports: u64,
+        space: u64,
+        owner: Pubkey,
+    },
+    Assign {
+        owner: Pubkey,
+    },
+    Transfer {
+        lamports: u64,
+    },
+    CreateAccountWithSeed {
+        base: Pubkey,
+        seed: String,
+        lamports: u64,
+        space: u64,
+        owner: Pubkey,
+    },
+    AdvanceNonceAccount,
+    WithdrawNonceAccount {
+        lamports: u64,
+    },
+    InitializeNonceAccount {
+        authority: Pubkey,
+    },
+    AuthorizeNonceAccount {
+        authority: Pubkey,
+    },
+    Allocate {
+        space: u64,
+    },
+    AllocateWithSeed {
+        base: Pubkey,
+        seed: String,
+        space: u64,
+        owner: Pubkey,
+    },
+    AssignWithSeed {
+        base: Pubkey,
+        seed: String,
+        owner: Pubkey,
+    },
+    TransferWithSeed {
+        lamports: u64,
+        from_seed: String,
+        from_owner: Pubkey,
+    },
+    UpgradeNonceAccount,
+}
+
+impl SystemIx {
+    /// Decode system program instruction data
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = Reader::new(buf);
+        let tag = reader.u32()?;
+        let ix = match tag {
+            0 => Self::CreateAccount {
+                lamports: reader.u64()?,
+                space: reader.u64()?,
+                owner: reader.pubkey()?,
+            },
+            1 => Self::Assign {
+                owner: reader.pubkey()?,
+            },
+            2 => Self::Transfer {
+                lamports: reader.u64()?,
+            },
+            3 => Self::CreateAccountWithSeed {
+                base: reader.pubkey()?,
+                seed: reader.bincode_string()?,
+                lamports: reader.u64()?,
+                space: reader.u64()?,
+                owner: reader.pubkey()?,
+            },
+            4 => Self::AdvanceNonceAccount,
+            5 => Self::WithdrawNonceAccount {
+                lamports: reader.u64()?,
+            },
+            6 => Self::InitializeNonceAccount {
+                authority: reader.pubkey()?,
+            },
+            7 => Self::AuthorizeNonceAccount {
+                authority: reader.pubkey()?,
+            },
+            8 => Self::Allocate {
+                space: reader.u64()?,
+            },
+            9 => Self::AllocateWithSeed {
+                base: reader.pubkey()?,
+                seed: reader.bincode_string()?,
+                space: reader.u64()?,
+                owner: reader.pubkey()?,
+            },
+            10 => Self::AssignWithSeed {
+                base: reader.pubkey()?,
+                seed: reader.bincode_string()?,
+                owner: reader.pubkey()?,
+            },
+            11 => Self::TransferWithSeed {
+                lamports: reader.u64()?,
+                from_seed: reader.bincode_string()?,
+                from_owner: reader.pubkey()?,
+            },
+            12 => Self::UpgradeNonceAccount,
+            _ => {
+                return Err(std::io::Error::other(format!(
+                    "System instruction tag {} not supported",
+                    tag
+                )))
+            }
+        };
+        Ok(ix)
+    }
+}
+
+/// Decoded system program instruction with the accounts it was invoked with
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemInstruction {
+    pub ix: SystemIx,
+    pub keys: SystemKeys,
+    pub accounts: Vec<AccountMeta>,
+}
+
+impl SystemInstruction {
+    /// Decode instruction data invoked with `accounts`
+    pub fn decode(data: &[u8], accounts: Vec<AccountMeta>) -> std::io::Result<Self> {
+        let ix = SystemIx::deserialize(data)?;
+        let pubkeys: Vec<Pubkey> = accounts.iter().map(|meta| meta.pubkey).collect();
+        let keys = SystemKeys::from_accounts(&ix, &pubkeys)?;
+        Ok(Self { ix, keys, accounts })
+    }
+
+    /// Lamports moved from one account to another, if this is a transfer
+    pub fn lamports_transfer(&self) -> Option<(Pubkey, Pubkey, u64)> {
+        match (&self.ix, &self.keys) {
+            (SystemIx::Transfer { lamports }, SystemKeys::Transfer(keys))
+            | (SystemIx::TransferWithSeed { lamports, .. }, SystemKeys::Transfer(keys)) => {
+                Some((keys.from, keys.to, *lamports))
+            }
+            (SystemIx::CreateAccount { lamports, .. }, SystemKeys::CreateAccount(keys))
+            | (SystemIx::CreateAccountWithSeed { lamports, .. }, SystemKeys::CreateAccount(keys)) => {
+                Some((keys.from, keys.to, *lamports))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Named accounts of a system program instruction
+#[derive(Clone, Debug, PartialEq)]
+pub enum SystemKeys {
+    CreateAccount(CreateAccountKeys),
+    Transfer(TransferKeys),
+    /// Instruction without named accounts; see `SystemInstruction::accounts`
+    Unnamed,
+}
+
+impl SystemKeys {
+    /// Name the `accounts` of `ix`; fails if there are fewer than the instruction expects
+    pub fn from_accounts(ix: &SystemIx, accounts: &[Pubkey]) -> std::io::Result<Self> {
+        match ix {
+            SystemIx::CreateAccount { .. } | SystemIx::CreateAccountWithSeed { .. } => {
+                Ok(Self::CreateAccount(keys_array(accounts)?.into()))
+            }
+            SystemIx::Transfer { .. } => Ok(Self::Transfer(keys_array(accounts)?.into())),
+            SystemIx::TransferWithSeed { .. } => {
+                let [from, _base, to] = keys_array(accounts)?;
+                Ok(Self::Transfer(TransferKeys { from, to }))
+            }
+            _ => Ok(Self::Unnamed),
+        }
+    }
+}
+
+pub const CREATE_ACCOUNT_IX_ACCOUNTS_LEN: usize = 2;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CreateAccountKeys {
+    pub from: Pubkey,
+    pub to: Pubkey,
+}
+impl From<[Pubkey; CREATE_ACCOUNT_IX_ACCOUNTS_LEN]> for CreateAccountKeys {
+    fn from(pubkeys: [Pubkey; CREATE_ACCOUNT_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            from: pubkeys[0],
+            to: pubkeys[1],
+        }
+    }
+}
+
+pub const TRANSFER_IX_ACCOUNTS_LEN: usize = 2;
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TransferKeys {
+    pub from: Pubkey,
+    pub to: Pubkey,
+}
+impl From<[Pubkey; TRANSFER_IX_ACCOUNTS_LEN]> for TransferKeys {
+    fn from(pubkeys: [Pubkey; TRANSFER_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            from: pubkeys[0],
+            to: pubkeys[1],
+        }
+    }
+}
+
+/// [`ProgramDecoder`] for the system program
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemProgramDecoder;
+
+impl ProgramDecoder for SystemProgramDecoder {
+    fn program_id(&self) -> Pubkey {
+        SYSTEM_PROGRAM_ID
+    }
+
+    fn name(&self) -> &str {
+        "system"
+    }
+
+    fn decode(
+        &self,
+        data: &[u8],
+        accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<DecodedInstruction> {
+        Ok(DecodedInstruction::System(Box::new(
+            SystemInstruction::decode(data, accounts)?,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::instruction::Instruction;
+    use solana_system_interface::instruction as system_instruction;
+
+    use super::*;
+
+    fn decode(instruction: Instruction) -> SystemInstruction {
+        assert_eq!(instruction.program_id, SYSTEM_PROGRAM_ID);
+        SystemInstruction::decode(&instruction.data, instruction.accounts).unwrap()
+    }
+
+    #[test]
+    fn transfer_moves_lamports() {
+        let (from, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = decode(system_instruction::transfer(&from, &to, 5_000));
+        assert_eq!(ix.ix, SystemIx::Transfer { lamports: 5_000 });
+        assert_eq!(ix.lamports_transfer(), Some((from, to, 5_000)));
+    }
+
+    #[test]
+    fn transfer_with_seed_skips_the_base_account() {
+        let (from, base, owner, to) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let ix = decode(system_instruction::transfer_with_seed(
+            &from,
+            &base,
+            "vault".to_string(),
+            &owner,
+            &to,
+            7,
+        ));
+        assert_eq!(
+            ix.ix,
+            SystemIx::TransferWithSeed {
+                lamports: 7,
+                from_seed: "vault".to_string(),
+                from_owner: owner,
+            }
+        );
+        assert_eq!(ix.lamports_transfer(), Some((from, to, 7)));
+    }
+
+    #[test]
+    fn create_account_funds_the_new_account() {
+        let (from, to, owner) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let ix = decode(system_instruction::create_account(
+            &from, &to, 1_461_600, 82, &owner,
+        ));
+        assert_eq!(
+            ix.ix,
+            SystemIx::CreateAccount {
+                lamports: 1_461_600,
+                space: 82,
+                owner,
+            }
+        );
+        assert_eq!(ix.lamports_transfer(), Some((from, to, 1_461_600)));
+
+        let base = Pubkey::new_unique();
+        let ix = decode(system_instruction::create_account_with_seed(
+            &from, &to, &base, "seed", 10, 0, &owner,
+        ));
+        assert_eq!(
+            ix.ix,
+            SystemIx::CreateAccountWithSeed {
+                base,
+                seed: "seed".to_string(),
+                lamports: 10,
+                space: 0,
+                owner,
+            }
+        );
+        assert_eq!(ix.lamports_transfer(), Some((from, to, 10)));
+    }
+
+    #[test]
+    fn decodes_account_and_nonce_instructions() {
+        let (account, base, owner) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let cases = [
+            (
+                system_instruction::assign(&account, &owner),
+                SystemIx::Assign { owner },
+            ),
+            (
+                system_instruction::allocate(&account, 165),
+                SystemIx::Allocate { space: 165 },
+            ),
+            (
+                system_instruction::allocate_with_seed(&account, &base, "a", 8, &owner),
+                SystemIx::AllocateWithSeed {
+                    base,
+                    seed: "a".to_string(),
+                    space: 8,
+                    owner,
+                },
+            ),
+            (
+                system_instruction::assign_with_seed(&account, &base, "b", &owner),
+                SystemIx::AssignWithSeed {
+                    base,
+                    seed: "b".to_string(),
+                    owner,
+                },
+            ),
+            (
+                system_instruction::advance_nonce_account(&account, &owner),
+                SystemIx::AdvanceNonceAccount,
+            ),
+            (
+                system_instruction::withdraw_nonce_account(&account, &owner, &base, 3),
+                SystemIx::WithdrawNonceAccount { lamports: 3 },
+            ),
+            (
+                system_instruction::authorize_nonce_account(&account, &owner, &base),
+                SystemIx::AuthorizeNonceAccount { authority: base },
+            ),
+            (
+                system_instruction::upgrade_nonce_account(account),
+                SystemIx::UpgradeNonceAccount,
+            ),
+        ];
+        for (instruction, expected) in cases {
+            let ix = decode(instruction);
+            assert_eq!(ix.ix, expected);
+            assert_eq!(ix.keys, SystemKeys::Unnamed);
+            assert_eq!(ix.lamports_transfer(), None);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_data() {
+        assert!(SystemIx::deserialize(&[2, 0, 0]).is_err());
+        assert!(SystemIx::deserialize(&13u32.to_le_bytes()).is_err());
+        assert!(SystemIx::deserialize(&[2, 0, 0, 0, 1, 2, 3]).is_err());
+
+        let transfer =
+            system_instruction::transfer(&Pubkey::new_unique(), &Pubkey::new_unique(), 1);
+        let accounts = transfer.accounts[..1].to_vec();
+        assert!(SystemInstruction::decode(&transfer.data, accounts).is_err());
+    }
+}
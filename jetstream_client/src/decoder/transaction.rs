@@ -0,0 +1,332 @@
+use jetstream_protos::jetstream::SubscribeUpdateTransactionInfo;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::v0::{self, MessageAddressTableLookup};
+use solana_sdk::message::{legacy, MessageHeader, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use super::bytes;
+
+/// Rebuild the signed transaction carried by a transaction update.
+///
+/// The message is v0 when the update has address table lookups and legacy
+/// otherwise. A v0 message without lookups is indistinguishable from a legacy
+/// one, so with `verify_signatures` a legacy message whose signatures fail is
+/// retried as v0 before giving up.
+pub fn versioned_transaction(
+    tx_info: &SubscribeUpdateTransactionInfo,
+    verify_signatures: bool,
+) -> anyhow::Result<VersionedTransaction> {
+    let signatures = bytes::signatures(tx_info)?;
+    let parts = MessageParts::new(tx_info)?;
+
+    let transaction = VersionedTransaction {
+        signatures,
+        message: if tx_info.address_table_lookups.is_empty() {
+            VersionedMessage::Legacy(parts.clone().into_legacy())
+        } else {
+            VersionedMessage::V0(parts.clone().into_v0(tx_info)?)
+        },
+    };
+    if !verify_signatures {
+        return Ok(transaction);
+    }
+
+    if let Err(e) = verify(&transaction) {
+        if !matches!(transaction.message, VersionedMessage::Legacy(_)) {
+            return Err(e);
+        }
+        let v0_transaction = VersionedTransaction {
+            signatures: transaction.signatures,
+            message: VersionedMessage::V0(parts.into_v0(tx_info)?),
+        };
+        verify(&v0_transaction).map_err(|_| e)?;
+        return Ok(v0_transaction);
+    }
+    Ok(transaction)
+}
+
+/// Check every signature of `transaction` against its message
+pub fn verify(transaction: &VersionedTransaction) -> anyhow::Result<()> {
+    let required = transaction.message.header().num_required_signatures as usize;
+    if transaction.signatures.len() != required {
+        return Err(anyhow::anyhow!(
+            "Transaction has {} signature(s), message requires {}",
+            transaction.signatures.len(),
+            required
+        ));
+    }
+
+    let failed: Vec<usize> = transaction
+        .verify_with_results()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, ok)| !ok)
+        .map(|(index, _)| index)
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Invalid signature(s) at index {:?} of transaction {}",
+            failed,
+            transaction.signatures[0]
+        ))
+    }
+}
+
+/// Fields shared by legacy and v0 messages
+#[derive(Clone)]
+struct MessageParts {
+    header: MessageHeader,
+    account_keys: Vec<Pubkey>,
+    recent_blockhash: Hash,
+    instructions: Vec<CompiledInstruction>,
+}
+
+impl MessageParts {
+    fn new(tx_info: &SubscribeUpdateTransactionInfo) -> anyhow::Result<Self> {
+        let header = MessageHeader {
+            num_required_signatures: header_count(
+                tx_info.num_required_signatures,
+                "num_required_signatures",
+            )?,
+            num_readonly_signed_accounts: header_count(
+                tx_info.num_readonly_signed_accounts,
+                "num_readonly_signed_accounts",
+            )?,
+            num_readonly_unsigned_accounts: header_count(
+                tx_info.num_readonly_unsigned_accounts,
+                "num_readonly_unsigned_accounts",
+            )?,
+        };
+
+        let account_keys = bytes::account_keys(tx_info, 0)?;
+        let recent_blockhash = bytes::recent_blockhash(tx_info)?;
+        let instructions = tx_info
+            .instructions
+            .iter()
+            .map(|instruction| {
+                Ok(CompiledInstruction {
+                    program_id_index: u8::try_from(instruction.program_id_index).map_err(|_| {
+                        anyhow::anyhow!(
+                            "Program id index {} out of range",
+                            instruction.program_id_index
+                        )
+                    })?,
+                    accounts: instruction.accounts.clone(),
+                    data: instruction.data.clone(),
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            header,
+            account_keys,
+            recent_blockhash,
+            instructions,
+        })
+    }
+
+    fn into_legacy(self) -> legacy::Message {
+        legacy::Message {
+            header: self.header,
+            account_keys: self.account_keys,
+            recent_blockhash: self.recent_blockhash,
+            instructions: self.instructions,
+        }
+    }
+
+    fn into_v0(self, tx_info: &SubscribeUpdateTransactionInfo) -> anyhow::Result<v0::Message> {
+        let address_table_lookups = tx_info
+            .address_table_lookups
+            .iter()
+            .enumerate()
+            .map(|(index, lookup)| {
+                Ok(MessageAddressTableLookup {
+                    account_key: bytes::lookup_table_key(index, lookup)?,
+                    writable_indexes: lookup.writable_indexes.clone(),
+                    readonly_indexes: lookup.readonly_indexes.clone(),
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(v0::Message {
+            header: self.header,
+            account_keys: self.account_keys,
+            recent_blockhash: self.recent_blockhash,
+            instructions: self.instructions,
+            address_table_lookups,
+        })
+    }
+}
+
+fn header_count(count: u32, field: &str) -> anyhow::Result<u8> {
+    u8::try_from(count)
+        .map_err(|_| anyhow::anyhow!("Message header {} of {} out of range", field, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use jetstream_protos::jetstream;
+    use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+    use solana_sdk::signature::{Keypair, Signer};
+    use solana_sdk::transaction::Transaction;
+    use solana_system_interface::instruction as system_instruction;
+
+    use super::*;
+
+    /// Transaction update carrying `transaction` as Jetstream streams it
+    fn tx_info(transaction: &VersionedTransaction) -> SubscribeUpdateTransactionInfo {
+        let message = &transaction.message;
+        let header = message.header();
+        SubscribeUpdateTransactionInfo {
+            signature: transaction.signatures[0].as_ref().to_vec(),
+            slot: 1,
+            num_required_signatures: header.num_required_signatures.into(),
+            num_readonly_signed_accounts: header.num_readonly_signed_accounts.into(),
+            num_readonly_unsigned_accounts: header.num_readonly_unsigned_accounts.into(),
+            recent_blockhash: message.recent_blockhash().as_ref().to_vec(),
+            signatures: transaction
+                .signatures
+                .iter()
+                .map(|signature| signature.as_ref().to_vec())
+                .collect(),
+            account_keys: message
+                .static_account_keys()
+                .iter()
+                .map(|key| key.to_bytes().to_vec())
+                .collect(),
+            instructions: message
+                .instructions()
+                .iter()
+                .map(|instruction| jetstream::CompiledInstruction {
+                    program_id_index: instruction.program_id_index.into(),
+                    accounts: instruction.accounts.clone(),
+                    data: instruction.data.clone(),
+                })
+                .collect(),
+            address_table_lookups: message
+                .address_table_lookups()
+                .unwrap_or_default()
+                .iter()
+                .map(|lookup| jetstream::MessageAddressTableLookup {
+                    account_key: lookup.account_key.to_bytes().to_vec(),
+                    writable_indexes: lookup.writable_indexes.clone(),
+                    readonly_indexes: lookup.readonly_indexes.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn legacy_transfer(payer: &Keypair) -> VersionedTransaction {
+        let transfer = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 42);
+        Transaction::new_signed_with_payer(
+            &[transfer],
+            Some(&payer.pubkey()),
+            &[payer],
+            Hash::new_unique(),
+        )
+        .into()
+    }
+
+    fn v0_transfer(
+        payer: &Keypair,
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> VersionedTransaction {
+        let to = lookup_tables
+            .first()
+            .map_or_else(Pubkey::new_unique, |table| table.addresses[1]);
+        let transfer = system_instruction::transfer(&payer.pubkey(), &to, 42);
+        let message = v0::Message::try_compile(
+            &payer.pubkey(),
+            &[transfer],
+            lookup_tables,
+            Hash::new_unique(),
+        )
+        .unwrap();
+        VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer]).unwrap()
+    }
+
+    #[test]
+    fn rebuilds_and_verifies_legacy_transaction() {
+        let transaction = legacy_transfer(&Keypair::new());
+        let rebuilt = versioned_transaction(&tx_info(&transaction), true).unwrap();
+        assert!(matches!(rebuilt.message, VersionedMessage::Legacy(_)));
+        assert_eq!(rebuilt, transaction);
+    }
+
+    #[test]
+    fn rebuilds_and_verifies_v0_transaction_with_lookups() {
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let transaction = v0_transfer(&Keypair::new(), std::slice::from_ref(&table));
+        let tx_info = tx_info(&transaction);
+        assert_eq!(tx_info.address_table_lookups.len(), 1);
+
+        let rebuilt = versioned_transaction(&tx_info, true).unwrap();
+        let VersionedMessage::V0(message) = &rebuilt.message else {
+            panic!("expected a v0 message");
+        };
+        assert_eq!(message.address_table_lookups[0].account_key, table.key);
+        assert_eq!(rebuilt, transaction);
+    }
+
+    #[test]
+    fn v0_transaction_without_lookups_is_retried_as_v0() {
+        let transaction = v0_transfer(&Keypair::new(), &[]);
+        let tx_info = tx_info(&transaction);
+        assert!(tx_info.address_table_lookups.is_empty());
+
+        let unverified = versioned_transaction(&tx_info, false).unwrap();
+        assert!(matches!(unverified.message, VersionedMessage::Legacy(_)));
+        assert!(verify(&unverified).is_err());
+
+        let rebuilt = versioned_transaction(&tx_info, true).unwrap();
+        assert!(matches!(rebuilt.message, VersionedMessage::V0(_)));
+        assert_eq!(rebuilt, transaction);
+    }
+
+    #[test]
+    fn tampered_transaction_fails_verification() {
+        let mut tx_info = tx_info(&legacy_transfer(&Keypair::new()));
+        tx_info.instructions[0].data[4] ^= 1;
+
+        assert!(versioned_transaction(&tx_info, false).is_ok());
+        let err = versioned_transaction(&tx_info, true).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid signature(s) at index [0]"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn signature_count_must_match_header() {
+        let mut tx_info = tx_info(&legacy_transfer(&Keypair::new()));
+        tx_info.num_required_signatures = 2;
+        let err = versioned_transaction(&tx_info, true).unwrap_err();
+        assert!(err.to_string().contains("message requires 2"), "{}", err);
+    }
+
+    #[test]
+    fn malformed_fields_are_rejected() {
+        let transaction = legacy_transfer(&Keypair::new());
+
+        let mut bad_header = tx_info(&transaction);
+        bad_header.num_readonly_unsigned_accounts = 256;
+        assert!(versioned_transaction(&bad_header, false).is_err());
+
+        let mut bad_program = tx_info(&transaction);
+        bad_program.instructions[0].program_id_index = 300;
+        assert!(versioned_transaction(&bad_program, false).is_err());
+
+        let mut bad_blockhash = tx_info(&transaction);
+        bad_blockhash.recent_blockhash.pop();
+        assert!(versioned_transaction(&bad_blockhash, false).is_err());
+    }
+}
@@ -0,0 +1,18 @@
+//! Client library for OrbitFlare Jetstream.
+//!
+//! Build a [`JetstreamClient`] with [`JetstreamClient::builder`] and call
+//! [`JetstreamClient::subscribe`] to get a typed stream of [`Update`]s.
+
+pub mod client;
+pub mod config;
+pub mod connector;
+pub mod decoder;
+pub mod record;
+pub mod replay;
+pub mod sink;
+pub mod update;
+
+pub use client::{JetstreamClient, JetstreamClientBuilder};
+pub use connector::stream::{ParsedUpdateStream, UpdateStream};
+pub use connector::subscription::SubscriptionHandle;
+pub use update::{RawUpdate, Update, UpdateKind};
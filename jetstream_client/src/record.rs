@@ -0,0 +1,185 @@
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
+use std::path::Path;
+use std::time::SystemTime;
+
+use jetstream_protos::jetstream::SubscribeRequest;
+use prost::Message;
+
+use crate::sink::COMPRESSED_EXTENSION;
+
+/// First bytes of every capture file
+pub const RECORD_MAGIC: [u8; 8] = *b"JSTREC01";
+
+/// Capture file extension
+pub const RECORD_EXTENSION: &str = "rec";
+
+/// Largest record accepted when reading, guarding against corrupt lengths
+const MAX_RECORD_LEN: u64 = 64 * 1024 * 1024;
+
+/// Describes a capture. A capture file is [`RECORD_MAGIC`], this header and then
+/// the updates, each a length-delimited `SubscribeUpdate` (or
+/// `SubscribeUpdateParsedTransaction` for parsed captures) exactly as received.
+#[derive(Clone, PartialEq, Message)]
+pub struct RecordHeader {
+    /// Format version, see [`RecordHeader::VERSION`]
+    #[prost(uint32, tag = "1")]
+    pub version: u32,
+    /// Endpoints the updates were received from
+    #[prost(string, repeated, tag = "2")]
+    pub endpoints: Vec<String>,
+    /// Subscription filters; unset for parsed captures
+    #[prost(message, optional, tag = "3")]
+    pub request: Option<SubscribeRequest>,
+    #[prost(message, optional, tag = "4")]
+    pub started_at: Option<prost_types::Timestamp>,
+    /// Updates are `SubscribeUpdateParsedTransaction`s
+    #[prost(bool, tag = "5")]
+    pub parsed: bool,
+}
+
+impl RecordHeader {
+    pub const VERSION: u32 = 1;
+
+    /// Header of a capture starting now
+    pub fn new(endpoints: Vec<String>, request: Option<SubscribeRequest>, parsed: bool) -> Self {
+        Self {
+            version: Self::VERSION,
+            endpoints,
+            request,
+            started_at: Some(SystemTime::now().into()),
+            parsed,
+        }
+    }
+
+    /// [`RECORD_MAGIC`] followed by the length-delimited header, written at the
+    /// start of every capture file
+    pub fn to_prefix(&self) -> Vec<u8> {
+        let mut prefix = RECORD_MAGIC.to_vec();
+        self.encode_length_delimited(&mut prefix)
+            .expect("Vec grows to fit the header");
+        prefix
+    }
+}
+
+/// The file ends in the middle of a record, as when its writer was interrupted
+#[derive(Debug)]
+pub struct TruncatedRecord;
+
+impl std::fmt::Display for TruncatedRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("record is truncated")
+    }
+}
+
+impl std::error::Error for TruncatedRecord {}
+
+/// Reads the messages of a capture file, or of a header-less file of
+/// length-delimited messages such as `--output protobuf` writes
+pub struct RecordReader {
+    reader: Box<dyn Read + Send>,
+    header: Option<RecordHeader>,
+    buf: Vec<u8>,
+}
+
+impl RecordReader {
+    /// Open the file at `path`, decompressing it if it ends in `.zst`
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))?;
+        let compressed = path
+            .extension()
+            .is_some_and(|extension| extension == COMPRESSED_EXTENSION);
+        let reader: Box<dyn Read + Send> = if compressed {
+            Box::new(zstd::stream::read::Decoder::new(file)?)
+        } else {
+            Box::new(file)
+        };
+        Self::new(reader).map_err(|e| anyhow::anyhow!("Failed to read {}: {:#}", path.display(), e))
+    }
+
+    /// Read from `reader`, parsing the header if it starts with [`RECORD_MAGIC`]
+    pub fn new(reader: impl Read + Send + 'static) -> anyhow::Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let mut prefix = Vec::with_capacity(RECORD_MAGIC.len());
+        reader
+            .by_ref()
+            .take(RECORD_MAGIC.len() as u64)
+            .read_to_end(&mut prefix)?;
+
+        if prefix != RECORD_MAGIC {
+            return Ok(Self {
+                reader: Box::new(Cursor::new(prefix).chain(reader)),
+                header: None,
+                buf: Vec::new(),
+            });
+        }
+
+        let mut record_reader = Self {
+            reader: Box::new(reader),
+            header: None,
+            buf: Vec::new(),
+        };
+        let header: RecordHeader = record_reader
+            .next_message()?
+            .ok_or_else(|| anyhow::anyhow!("Capture header is missing"))?;
+        if header.version > RecordHeader::VERSION {
+            return Err(anyhow::anyhow!(
+                "Capture format version {} is not supported",
+                header.version
+            ));
+        }
+        record_reader.header = Some(header);
+        Ok(record_reader)
+    }
+
+    /// Header of the capture, `None` for header-less files
+    pub fn header(&self) -> Option<&RecordHeader> {
+        self.header.as_ref()
+    }
+
+    /// Next length-delimited message, `None` at the end of the file. A file ending
+    /// mid-record fails with a [`TruncatedRecord`] error.
+    pub fn next_message<M: Message + Default>(&mut self) -> anyhow::Result<Option<M>> {
+        let Some(len) = self.read_length()? else {
+            return Ok(None);
+        };
+        if len > MAX_RECORD_LEN {
+            return Err(anyhow::anyhow!("Record of {} bytes is too large", len));
+        }
+        self.buf.resize(len as usize, 0);
+        self.reader
+            .read_exact(&mut self.buf)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => anyhow::Error::new(TruncatedRecord)
+                    .context(format!("Record of {} bytes is truncated", len)),
+                _ => anyhow::anyhow!("Failed to read record of {} bytes: {}", len, e),
+            })?;
+        Ok(Some(M::decode(self.buf.as_slice())?))
+    }
+
+    /// Varint length prefix, `None` at a clean end of file
+    fn read_length(&mut self) -> anyhow::Result<Option<u64>> {
+        let mut len = 0u64;
+        for shift in (0..64).step_by(7) {
+            let mut byte = [0u8];
+            match self.reader.read_exact(&mut byte) {
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof && shift == 0 => {
+                    return Ok(None)
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    return Err(
+                        anyhow::Error::new(TruncatedRecord).context("Record length is truncated")
+                    )
+                }
+                Err(e) => return Err(anyhow::anyhow!("Failed to read record length: {}", e)),
+                Ok(()) => {}
+            }
+            len |= u64::from(byte[0] & 0x7f) << shift;
+            if byte[0] & 0x80 == 0 {
+                return Ok(Some(len));
+            }
+        }
+        Err(anyhow::anyhow!("Record length is not a valid varint"))
+    }
+}
@@ -0,0 +1,370 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use jetstream_protos::jetstream::SubscribeUpdate;
+use tokio::sync::mpsc;
+
+use crate::connector::race::RaceTracker;
+use crate::connector::stream::{AbortOnDrop, UpdateStream, STREAM_BUFFER};
+use crate::record::{RecordReader, TruncatedRecord, RECORD_EXTENSION};
+use crate::sink::COMPRESSED_EXTENSION;
+use crate::update::Update;
+
+/// Extension of header-less `SubscribeUpdate` files
+const PROTOBUF_EXTENSION: &str = "pb";
+
+/// Recent updates remembered to drop the copies other raced endpoints delivered
+const REPLAY_DEDUP_CAPACITY: usize = 100_000;
+
+/// Pace at which captured updates are replayed
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ReplaySpeed {
+    /// As fast as the consumer keeps up
+    #[default]
+    Max,
+    /// Recorded `created_at` spacing divided by this factor; 1.0 is real time
+    Scaled(f64),
+}
+
+impl FromStr for ReplaySpeed {
+    type Err = String;
+
+    /// `max`, `realtime`, or a positive factor such as `2`, `0.5` or `10x`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "max" => Ok(Self::Max),
+            "realtime" => Ok(Self::Scaled(1.0)),
+            _ => {
+                let factor: f64 = s
+                    .strip_suffix('x')
+                    .unwrap_or(s)
+                    .parse()
+                    .map_err(|_| format!("expected max, realtime or a factor, got '{}'", s))?;
+                if !factor.is_finite() || factor <= 0.0 {
+                    return Err(format!("expected a positive factor, got '{}'", s));
+                }
+                Ok(Self::Scaled(factor))
+            }
+        }
+    }
+}
+
+/// Capture files at `path`: the file itself, or the `.rec` and `.pb` files
+/// (compressed or not) of a directory in name order, which is recording order
+/// for segments written by [`crate::sink::RotatingFileSink`]
+pub fn capture_files(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?
+    {
+        let file = entry?.path();
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        let name = name
+            .strip_suffix(&format!(".{}", COMPRESSED_EXTENSION))
+            .unwrap_or(&name);
+        let is_capture = [RECORD_EXTENSION, PROTOBUF_EXTENSION]
+            .iter()
+            .any(|extension| name.ends_with(&format!(".{}", extension)));
+        if is_capture {
+            files.push(file);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Stream the updates captured in `files`, in order, like a live subscription.
+/// Copies of an update recorded from several raced endpoints are dropped as they
+/// were live. Updates are attributed to endpoint 0 and the stream ends after the last file,
+/// or with an error at a corrupt record; a truncated last record only ends its file.
+pub fn replay(files: Vec<PathBuf>, speed: ReplaySpeed) -> UpdateStream {
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    let task = tokio::task::spawn_blocking(move || {
+        if let Err(e) = replay_files(&files, speed, &tx) {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+    UpdateStream::new(
+        rx,
+        Arc::new(AtomicU64::new(0)),
+        vec![AbortOnDrop::new(task)],
+    )
+}
+
+/// Send the updates of every file, stopping quietly once the stream is dropped
+fn replay_files(
+    files: &[PathBuf],
+    speed: ReplaySpeed,
+    tx: &mpsc::Sender<anyhow::Result<Update>>,
+) -> anyhow::Result<()> {
+    // Wall clock and capture clock at the first timestamped update
+    let mut origin: Option<(Instant, Duration)> = None;
+    let mut race = RaceTracker::new(vec!["replay".to_string()], REPLAY_DEDUP_CAPACITY);
+    let mut warned_untimed = false;
+
+    for path in files {
+        let mut reader = RecordReader::open(path)?;
+        if reader.header().is_some_and(|header| header.parsed) {
+            return Err(anyhow::anyhow!(
+                "{} holds parsed transactions, which cannot be replayed",
+                path.display()
+            ));
+        }
+        log::info!("Replaying {}", path.display());
+
+        loop {
+            let update = match reader.next_message::<SubscribeUpdate>() {
+                Ok(Some(update)) => update,
+                Ok(None) => break,
+                Err(e) if e.downcast_ref::<TruncatedRecord>().is_some() => {
+                    log::warn!("Stopped reading {}: {:#}", path.display(), e);
+                    break;
+                }
+                Err(e) => return Err(e.context(format!("Failed to replay {}", path.display()))),
+            };
+
+            let Some(update) = Update::from_proto(0, update) else {
+                continue;
+            };
+            if !race.first_arrival(0, &update.dedup_key()) {
+                continue;
+            }
+
+            if let ReplaySpeed::Scaled(factor) = speed {
+                match update.created_at.and_then(timestamp_offset) {
+                    Some(created_at) => {
+                        let (started, first) = *origin.get_or_insert((Instant::now(), created_at));
+                        let due = started + created_at.saturating_sub(first).div_f64(factor);
+                        let wait = due.saturating_duration_since(Instant::now());
+                        if !wait.is_zero() {
+                            std::thread::sleep(wait);
+                        }
+                    }
+                    None if !warned_untimed => {
+                        log::warn!(
+                            "{} has updates without created_at, which are replayed without pacing",
+                            path.display()
+                        );
+                        warned_untimed = true;
+                    }
+                    None => {}
+                }
+            }
+            if tx.blocking_send(Ok(update)).is_err() {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Time since the Unix epoch, `None` before it
+fn timestamp_offset(timestamp: prost_types::Timestamp) -> Option<Duration> {
+    let seconds = u64::try_from(timestamp.seconds).ok()?;
+    let nanos = u32::try_from(timestamp.nanos).ok()?;
+    Some(Duration::new(seconds, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::RecordHeader;
+    use crate::update::UpdateKind;
+    use jetstream_protos::jetstream::{
+        subscribe_update::UpdateOneof, SubscribeUpdatePing, SubscribeUpdateTransaction,
+        SubscribeUpdateTransactionInfo,
+    };
+    use prost::Message;
+    use tokio_stream::StreamExt;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("replay-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn transaction(id: u8, created_at_millis: Option<i64>) -> SubscribeUpdate {
+        SubscribeUpdate {
+            filters: vec!["replay".to_string()],
+            created_at: created_at_millis.map(|millis| prost_types::Timestamp {
+                seconds: millis / 1000,
+                nanos: (millis % 1000) as i32 * 1_000_000,
+            }),
+            update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+                transaction: Some(SubscribeUpdateTransactionInfo {
+                    signature: vec![id; 64],
+                    slot: id as u64,
+                    ..Default::default()
+                }),
+                slot: id as u64,
+            })),
+        }
+    }
+
+    fn ping() -> SubscribeUpdate {
+        SubscribeUpdate {
+            filters: Vec::new(),
+            created_at: None,
+            update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+        }
+    }
+
+    /// Capture of `updates` after a header
+    fn capture(parsed: bool, updates: &[SubscribeUpdate]) -> Vec<u8> {
+        let mut data = RecordHeader::new(vec!["a".into(), "b".into()], None, parsed).to_prefix();
+        for update in updates {
+            update.encode_length_delimited(&mut data).unwrap();
+        }
+        data
+    }
+
+    /// Signatures replayed from `files`, or the error the stream ended with
+    async fn replayed(files: Vec<PathBuf>, speed: ReplaySpeed) -> (Vec<u8>, Option<String>) {
+        let mut stream = replay(files, speed);
+        let mut ids = Vec::new();
+        while let Some(update) = stream.next().await {
+            match update {
+                Ok(update) => match update.kind {
+                    UpdateKind::Transaction(tx_info) => ids.push(tx_info.signature[0]),
+                    UpdateKind::Account(_) => panic!("unexpected account update"),
+                },
+                Err(e) => return (ids, Some(format!("{:#}", e))),
+            }
+        }
+        (ids, None)
+    }
+
+    #[test]
+    fn speed_parses() {
+        assert_eq!("max".parse(), Ok(ReplaySpeed::Max));
+        assert_eq!("realtime".parse(), Ok(ReplaySpeed::Scaled(1.0)));
+        assert_eq!("2".parse(), Ok(ReplaySpeed::Scaled(2.0)));
+        assert_eq!("0.5".parse(), Ok(ReplaySpeed::Scaled(0.5)));
+        assert_eq!("10x".parse(), Ok(ReplaySpeed::Scaled(10.0)));
+        for invalid in ["0", "-1", "inf", "NaN", "fast", ""] {
+            assert!(invalid.parse::<ReplaySpeed>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn capture_files_are_listed_in_name_order() {
+        let dir = temp_dir("files");
+        for name in ["c.pb", "b.rec", "a.rec.zst", "notes.txt", "d.jsonl.zst"] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+        let names: Vec<_> = capture_files(&dir)
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["a.rec.zst", "b.rec", "c.pb"]);
+
+        let file = dir.join("b.rec");
+        assert_eq!(capture_files(&file).unwrap(), [file]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn duplicates_from_raced_endpoints_are_dropped() {
+        let dir = temp_dir("dedup");
+        let first = dir.join("a.rec");
+        let second = dir.join("b.rec.zst");
+        fs::write(
+            &first,
+            capture(
+                false,
+                &[
+                    transaction(1, None),
+                    ping(),
+                    transaction(1, None),
+                    transaction(2, None),
+                ],
+            ),
+        )
+        .unwrap();
+        let data = capture(false, &[transaction(2, None), transaction(3, None)]);
+        fs::write(&second, zstd::encode_all(data.as_slice(), 0).unwrap()).unwrap();
+
+        let files = capture_files(&dir).unwrap();
+        assert_eq!(
+            replayed(files, ReplaySpeed::Max).await,
+            (vec![1, 2, 3], None)
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn parsed_captures_are_rejected() {
+        let dir = temp_dir("parsed");
+        let path = dir.join("parsed.rec");
+        fs::write(&path, capture(true, &[])).unwrap();
+
+        let (ids, error) = replayed(vec![path], ReplaySpeed::Max).await;
+        assert!(ids.is_empty());
+        assert!(error.unwrap().contains("parsed transactions"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn corrupt_records_fail_but_truncated_tails_end_the_file() {
+        let dir = temp_dir("corrupt");
+        let corrupt = dir.join("corrupt.rec");
+        let mut data = capture(false, &[transaction(1, None)]);
+        // A length-delimited record that is not a SubscribeUpdate
+        data.extend_from_slice(&[3, 0xff, 0xff, 0xff]);
+        transaction(2, None)
+            .encode_length_delimited(&mut data)
+            .unwrap();
+        fs::write(&corrupt, data).unwrap();
+
+        let (ids, error) = replayed(vec![corrupt], ReplaySpeed::Max).await;
+        assert_eq!(ids, [1]);
+        assert!(error.unwrap().contains("corrupt.rec"));
+
+        let truncated = dir.join("truncated.rec");
+        let mut data = capture(false, &[transaction(1, None)]);
+        let record = transaction(2, None).encode_length_delimited_to_vec();
+        data.extend_from_slice(&record[..record.len() / 2]);
+        fs::write(&truncated, data).unwrap();
+        let next = dir.join("next.rec");
+        fs::write(&next, capture(false, &[transaction(3, None)])).unwrap();
+
+        let ended = replayed(vec![truncated, next], ReplaySpeed::Max).await;
+        assert_eq!(ended, (vec![1, 3], None));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn scaled_speed_follows_the_recorded_spacing() {
+        let dir = temp_dir("pace");
+        let path = dir.join("pace.rec");
+        let updates = [
+            transaction(1, Some(1_000)),
+            transaction(2, Some(1_200)),
+            transaction(3, Some(1_400)),
+        ];
+        fs::write(&path, capture(false, &updates)).unwrap();
+
+        let started = Instant::now();
+        let paced = replayed(vec![path.clone()], ReplaySpeed::Scaled(2.0)).await;
+        let elapsed = started.elapsed();
+        assert_eq!(paced, (vec![1, 2, 3], None));
+        // 400ms of recording at twice the pace
+        assert!(elapsed >= Duration::from_millis(200), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(2), "{:?}", elapsed);
+
+        let started = Instant::now();
+        assert_eq!(replayed(vec![path], ReplaySpeed::Max).await.0, [1, 2, 3]);
+        assert!(started.elapsed() < Duration::from_millis(200));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,517 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Extension appended to compressed segments
+pub const COMPRESSED_EXTENSION: &str = "zst";
+
+/// When segments roll over and how closed ones are kept
+#[derive(Debug, Clone, Default)]
+pub struct RotationConfig {
+    /// Start a new segment once the current one holds this many bytes
+    pub max_bytes: Option<u64>,
+    /// Start a new segment at every multiple of this wall-clock interval (1h rolls over on the hour)
+    pub interval: Option<Duration>,
+    /// Start a new segment once a record reaches the next multiple of this many slots
+    pub slot_interval: Option<u64>,
+    /// Compress closed segments with zstd
+    pub compress: bool,
+    /// Keep at most this many closed segments, deleting the oldest
+    pub max_segments: Option<usize>,
+    /// Keep at most this many bytes of closed segments, deleting the oldest
+    pub max_total_bytes: Option<u64>,
+}
+
+/// Appends records to `<prefix>-<unix millis>-<seq>.<extension>` files in a
+/// directory, rolling over according to a [`RotationConfig`]. A record is never
+/// split across segments.
+pub struct RotatingFileSink {
+    dir: PathBuf,
+    prefix: String,
+    extension: String,
+    config: RotationConfig,
+    /// Written at the start of every segment
+    header: Vec<u8>,
+    segment: Option<Segment>,
+    next_seq: u64,
+    /// Compression and retention of the last closed segment
+    finisher: Option<JoinHandle<()>>,
+}
+
+struct Segment {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    bytes: u64,
+    period: Option<u64>,
+    slot_period: Option<u64>,
+}
+
+impl RotatingFileSink {
+    /// Sink writing `<prefix>-*.<extension>` segments to `dir`, which is created if missing
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        prefix: &str,
+        extension: &str,
+        config: RotationConfig,
+    ) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", dir.display(), e))?;
+        Ok(Self {
+            dir,
+            prefix: prefix.to_string(),
+            extension: extension.to_string(),
+            config,
+            header: Vec::new(),
+            segment: None,
+            next_seq: 0,
+            finisher: None,
+        })
+    }
+
+    /// Start every segment with `header`, so each one can be read on its own
+    pub fn with_header(mut self, header: Vec<u8>) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Append `record`, first rolling over if the record's `slot`, the clock or
+    /// the segment size call for a new segment
+    pub fn write_record(&mut self, record: &[u8], slot: Option<u64>) -> anyhow::Result<()> {
+        let period = self.period();
+        let slot_period = self.slot_period(slot);
+        let rotate = self.segment.as_ref().is_some_and(|segment| {
+            let full = self
+                .config
+                .max_bytes
+                .is_some_and(|max| segment.bytes > 0 && segment.bytes + record.len() as u64 > max);
+            // Late updates for an earlier slot period stay in the current segment
+            let new_slot_period = matches!(
+                (slot_period, segment.slot_period),
+                (Some(new), Some(current)) if new > current
+            );
+            full || segment.period != period || new_slot_period
+        });
+        if rotate {
+            self.close_segment()?;
+        }
+
+        if self.segment.is_none() {
+            self.segment = Some(self.open_segment(period, slot_period)?);
+        }
+        let segment = self.segment.as_mut().expect("segment opened above");
+        segment
+            .writer
+            .write_all(record)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", segment.path.display(), e))?;
+        segment.bytes += record.len() as u64;
+        if segment.slot_period.is_none() {
+            segment.slot_period = slot_period;
+        }
+        Ok(())
+    }
+
+    /// Flush buffered records of the open segment
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        if let Some(segment) = &mut self.segment {
+            segment.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Close the open segment and wait for its compression and retention
+    pub fn close(&mut self) -> anyhow::Result<()> {
+        self.close_segment()?;
+        self.join_finisher();
+        Ok(())
+    }
+
+    /// Path of the segment currently written to
+    pub fn current_path(&self) -> Option<&Path> {
+        self.segment.as_ref().map(|segment| segment.path.as_path())
+    }
+
+    fn period(&self) -> Option<u64> {
+        let interval = self.config.interval?.as_millis().max(1);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        Some((now / interval) as u64)
+    }
+
+    fn slot_period(&self, slot: Option<u64>) -> Option<u64> {
+        Some(slot? / self.config.slot_interval?.max(1))
+    }
+
+    fn open_segment(
+        &mut self,
+        period: Option<u64>,
+        slot_period: Option<u64>,
+    ) -> anyhow::Result<Segment> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = self.dir.join(format!(
+            "{}-{:013}-{:06}.{}",
+            self.prefix, millis, self.next_seq, self.extension
+        ));
+        self.next_seq += 1;
+
+        let file = OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .open(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", path.display(), e))?;
+        let mut writer = BufWriter::new(file);
+        writer
+            .write_all(&self.header)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+        log::info!("Writing output segment {}", path.display());
+        Ok(Segment {
+            path,
+            writer,
+            bytes: self.header.len() as u64,
+            period,
+            slot_period,
+        })
+    }
+
+    fn close_segment(&mut self) -> anyhow::Result<()> {
+        let Some(mut segment) = self.segment.take() else {
+            return Ok(());
+        };
+        segment
+            .writer
+            .flush()
+            .map_err(|e| anyhow::anyhow!("Failed to flush {}: {}", segment.path.display(), e))?;
+        drop(segment.writer);
+
+        // Finish one segment at a time so retention never races a compression
+        self.join_finisher();
+        let finished = Finished {
+            path: segment.path,
+            dir: self.dir.clone(),
+            prefix: format!("{}-", self.prefix),
+            extension: self.extension.clone(),
+            config: self.config.clone(),
+        };
+        self.finisher = Some(std::thread::spawn(move || finished.run()));
+        Ok(())
+    }
+
+    fn join_finisher(&mut self) {
+        if let Some(finisher) = self.finisher.take() {
+            if finisher.join().is_err() {
+                log::warn!("Output segment compression thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for RotatingFileSink {
+    fn drop(&mut self) {
+        if let Err(e) = self.close() {
+            log::warn!("Failed to close output segment: {:#}", e);
+        }
+    }
+}
+
+/// Closed segment awaiting compression and retention
+struct Finished {
+    path: PathBuf,
+    dir: PathBuf,
+    prefix: String,
+    extension: String,
+    config: RotationConfig,
+}
+
+impl Finished {
+    fn run(self) {
+        if self.config.compress {
+            if let Err(e) = compress(&self.path) {
+                log::warn!("Failed to compress {}: {:#}", self.path.display(), e);
+            }
+        }
+        if let Err(e) = self.enforce_retention() {
+            log::warn!(
+                "Failed to apply retention in {}: {:#}",
+                self.dir.display(),
+                e
+            );
+        }
+    }
+
+    /// Delete the oldest closed segments past the count and size limits. Segments
+    /// named after the one just closed are still being written and are left alone.
+    fn enforce_retention(&self) -> anyhow::Result<()> {
+        if self.config.max_segments.is_none() && self.config.max_total_bytes.is_none() {
+            return Ok(());
+        }
+        let plain_suffix = format!(".{}", self.extension);
+        let compressed_suffix = format!(".{}.{}", self.extension, COMPRESSED_EXTENSION);
+        let Some(closed_stem) = self
+            .path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(&plain_suffix))
+        else {
+            return Ok(());
+        };
+
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let stem = name
+                .strip_suffix(&compressed_suffix)
+                .or_else(|| name.strip_suffix(&plain_suffix));
+            let is_closed = stem.is_some_and(|stem| {
+                stem.strip_prefix(&self.prefix)
+                    .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+                    && stem <= closed_stem
+            });
+            if is_closed {
+                segments.push((name, entry.metadata()?.len()));
+            }
+        }
+        segments.sort();
+
+        let mut total: u64 = segments.iter().map(|(_, len)| len).sum();
+        let mut count = segments.len();
+        for (name, len) in segments {
+            let over_count = self.config.max_segments.is_some_and(|max| count > max);
+            let over_size = self.config.max_total_bytes.is_some_and(|max| total > max);
+            if !over_count && !over_size {
+                break;
+            }
+            let path = self.dir.join(&name);
+            match fs::remove_file(&path) {
+                Ok(()) => log::info!("Deleted output segment {}", path.display()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+            total -= len;
+            count -= 1;
+        }
+        Ok(())
+    }
+}
+
+/// Replace `path` with a zstd compressed `<path>.zst`
+fn compress(path: &Path) -> anyhow::Result<()> {
+    let mut compressed_path = path.as_os_str().to_owned();
+    compressed_path.push(format!(".{}", COMPRESSED_EXTENSION));
+    let compressed_path = PathBuf::from(compressed_path);
+
+    let input = File::open(path)?;
+    let mut output = BufWriter::new(File::create(&compressed_path)?);
+    zstd::stream::copy_encode(input, &mut output, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+    output
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sink-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Contents of the segments in `dir`, oldest first, decompressing `.zst` ones
+    fn segments(dir: &Path) -> Vec<(String, Vec<u8>)> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| {
+                let data = fs::read(dir.join(&name)).unwrap();
+                let data = match name.ends_with(COMPRESSED_EXTENSION) {
+                    true => zstd::decode_all(data.as_slice()).unwrap(),
+                    false => data,
+                };
+                (name, data)
+            })
+            .collect()
+    }
+
+    fn contents(dir: &Path) -> Vec<Vec<u8>> {
+        segments(dir).into_iter().map(|(_, data)| data).collect()
+    }
+
+    fn test_sink(dir: &Path, config: RotationConfig) -> RotatingFileSink {
+        RotatingFileSink::new(dir, "updates", "bin", config)
+            .unwrap()
+            .with_header(b"H".to_vec())
+    }
+
+    #[test]
+    fn size_rollover_keeps_records_whole() {
+        let dir = temp_dir("size");
+        let mut sink = test_sink(
+            &dir,
+            RotationConfig {
+                max_bytes: Some(8),
+                ..Default::default()
+            },
+        );
+        for record in [b"aaa", b"bbb", b"ccc", b"ddd"] {
+            sink.write_record(record, None).unwrap();
+        }
+        // A record larger than the limit still gets a segment of its own
+        sink.write_record(b"0123456789", None).unwrap();
+        sink.close().unwrap();
+
+        assert_eq!(
+            contents(&dir),
+            [&b"Haaabbb"[..], b"Hcccddd", b"H0123456789"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn slot_rollover_only_moves_forward() {
+        let dir = temp_dir("slot");
+        let mut sink = test_sink(
+            &dir,
+            RotationConfig {
+                slot_interval: Some(10),
+                ..Default::default()
+            },
+        );
+        for (record, slot) in [
+            (b"a", Some(5)),
+            (b"b", Some(12)),
+            // Late updates for slot period 0 arrive after period 1 started
+            (b"c", Some(9)),
+            (b"d", None),
+            (b"e", Some(13)),
+            (b"f", Some(25)),
+        ] {
+            sink.write_record(record, slot).unwrap();
+        }
+        sink.close().unwrap();
+
+        assert_eq!(contents(&dir), [&b"Ha"[..], b"Hbcde", b"Hf"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn time_rollover_starts_a_segment_per_interval() {
+        let dir = temp_dir("time");
+        let mut sink = test_sink(
+            &dir,
+            RotationConfig {
+                interval: Some(Duration::from_millis(50)),
+                ..Default::default()
+            },
+        );
+        sink.write_record(b"a", None).unwrap();
+        let first = sink.current_path().unwrap().to_path_buf();
+        std::thread::sleep(Duration::from_millis(60));
+        sink.write_record(b"b", None).unwrap();
+        assert_ne!(sink.current_path().unwrap(), first);
+        sink.close().unwrap();
+
+        assert_eq!(contents(&dir), [&b"Ha"[..], b"Hb"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn closed_segments_are_compressed() {
+        let dir = temp_dir("compress");
+        let mut sink = test_sink(
+            &dir,
+            RotationConfig {
+                slot_interval: Some(1),
+                compress: true,
+                ..Default::default()
+            },
+        );
+        sink.write_record(b"a", Some(1)).unwrap();
+        sink.write_record(b"b", Some(2)).unwrap();
+        sink.close().unwrap();
+
+        let segments = segments(&dir);
+        assert!(segments.iter().all(|(name, _)| name.ends_with(".bin.zst")));
+        assert_eq!(
+            segments
+                .into_iter()
+                .map(|(_, data)| data)
+                .collect::<Vec<_>>(),
+            [&b"Ha"[..], b"Hb"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn retention_deletes_the_oldest_segments_but_never_the_open_one() {
+        let dir = temp_dir("count");
+        let mut sink = test_sink(
+            &dir,
+            RotationConfig {
+                slot_interval: Some(1),
+                max_segments: Some(0),
+                ..Default::default()
+            },
+        );
+        for slot in 0..4 {
+            sink.write_record(b"x", Some(slot)).unwrap();
+            sink.join_finisher();
+            let open = sink.current_path().unwrap().to_path_buf();
+            assert!(open.exists());
+            assert_eq!(segments(&dir).len(), 1);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+
+        let dir = temp_dir("retain");
+        let mut sink = test_sink(
+            &dir,
+            RotationConfig {
+                slot_interval: Some(1),
+                max_segments: Some(2),
+                ..Default::default()
+            },
+        );
+        for (slot, record) in [b"a", b"b", b"c", b"d"].iter().enumerate() {
+            sink.write_record(*record, Some(slot as u64)).unwrap();
+        }
+        sink.close().unwrap();
+        assert_eq!(contents(&dir), [&b"Hc"[..], b"Hd"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn retention_limits_total_bytes() {
+        let dir = temp_dir("bytes");
+        let mut sink = test_sink(
+            &dir,
+            RotationConfig {
+                slot_interval: Some(1),
+                max_total_bytes: Some(10),
+                ..Default::default()
+            },
+        );
+        for (slot, record) in [b"aaaa", b"bbbb", b"cccc", b"dddd"].iter().enumerate() {
+            sink.write_record(*record, Some(slot as u64)).unwrap();
+        }
+        sink.close().unwrap();
+
+        // Each segment is 5 bytes, so two fit in the budget
+        assert_eq!(contents(&dir), [&b"Hcccc"[..], b"Hdddd"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
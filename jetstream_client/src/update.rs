@@ -0,0 +1,89 @@
+use jetstream_protos::jetstream::{
+    subscribe_update::UpdateOneof, SubscribeUpdate, SubscribeUpdateAccount,
+    SubscribeUpdateTransactionInfo,
+};
+use prost::bytes::Bytes;
+
+/// A deduplicated update received from one of the raced endpoints
+#[derive(Debug, Clone)]
+pub struct Update {
+    /// Index of the endpoint that delivered the update first
+    pub endpoint: usize,
+    /// Names of the filters that matched the update
+    pub filters: Vec<String>,
+    pub created_at: Option<prost_types::Timestamp>,
+    pub kind: UpdateKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum UpdateKind {
+    Transaction(SubscribeUpdateTransactionInfo),
+    Account(SubscribeUpdateAccount),
+}
+
+impl Update {
+    /// Convert a raw `SubscribeUpdate`, ignoring pings, pongs and empty updates
+    pub(crate) fn from_proto(endpoint: usize, update: SubscribeUpdate) -> Option<Self> {
+        let kind = match update.update_oneof? {
+            UpdateOneof::Transaction(tx_update) => UpdateKind::Transaction(tx_update.transaction?),
+            UpdateOneof::Account(account_update) => {
+                account_update.account.as_ref()?;
+                UpdateKind::Account(account_update)
+            }
+            UpdateOneof::Ping(_) | UpdateOneof::Pong(_) => return None,
+        };
+
+        Some(Self {
+            endpoint,
+            filters: update.filters,
+            created_at: update.created_at,
+            kind,
+        })
+    }
+
+    /// Slot of the transaction or account write
+    pub fn slot(&self) -> u64 {
+        match &self.kind {
+            UpdateKind::Transaction(tx_info) => tx_info.slot,
+            UpdateKind::Account(account_update) => account_update.slot,
+        }
+    }
+
+    /// Key identifying the same update across endpoints
+    pub(crate) fn dedup_key(&self) -> Vec<u8> {
+        match &self.kind {
+            UpdateKind::Transaction(tx_info) => tx_info.signature.clone(),
+            UpdateKind::Account(account_update) => {
+                let Some(account) = &account_update.account else {
+                    return Vec::new();
+                };
+                let mut key = account.pubkey.clone();
+                key.extend_from_slice(&account.write_version.to_le_bytes());
+                key
+            }
+        }
+    }
+}
+
+/// A `SubscribeUpdate` exactly as an endpoint sent it, before deduplication;
+/// pings and pongs included. See [`crate::JetstreamClient::subscribe_with_tap`],
+/// and [`crate::JetstreamClient::subscribe_parsed_with_tap`] for
+/// `SubscribeUpdateParsedTransaction`s.
+#[derive(Debug, Clone)]
+pub struct RawUpdate {
+    /// Index of the endpoint that sent the message
+    pub endpoint: usize,
+    /// Protobuf encoding of the message as received
+    pub bytes: Bytes,
+    /// Slot of the transaction or account write, `None` for pings and pongs
+    pub slot: Option<u64>,
+}
+
+/// Slot of a transaction or account `SubscribeUpdate`
+pub(crate) fn proto_slot(update: &SubscribeUpdate) -> Option<u64> {
+    match update.update_oneof.as_ref()? {
+        UpdateOneof::Transaction(tx_update) => Some(tx_update.slot),
+        UpdateOneof::Account(account_update) => Some(account_update.slot),
+        UpdateOneof::Ping(_) | UpdateOneof::Pong(_) => None,
+    }
+}
@@ -0,0 +1,159 @@
+use std::pin::Pin;
+use std::time::Duration;
+
+use jetstream_client::config::ReconnectConfig;
+use jetstream_client::{JetstreamClient, UpdateKind};
+use jetstream_protos::jetstream::jetstream_server::{Jetstream, JetstreamServer};
+use jetstream_protos::jetstream::{
+    subscribe_update::UpdateOneof, GetVersionRequest, GetVersionResponse, PingRequest,
+    PongResponse, SubscribeParsedRequest, SubscribeRequest, SubscribeUpdate,
+    SubscribeUpdateParsedTransaction, SubscribeUpdatePing, SubscribeUpdateTransaction,
+    SubscribeUpdateTransactionInfo,
+};
+use prost::Message;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+fn ping() -> SubscribeUpdate {
+    SubscribeUpdate {
+        filters: Vec::new(),
+        created_at: None,
+        update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+    }
+}
+
+fn transaction() -> SubscribeUpdate {
+    SubscribeUpdate {
+        filters: vec!["tap".to_string()],
+        created_at: None,
+        update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: vec![7; 64],
+                slot: 42,
+                ..Default::default()
+            }),
+            slot: 42,
+        })),
+    }
+}
+
+fn parsed_transaction() -> SubscribeUpdateParsedTransaction {
+    SubscribeUpdateParsedTransaction {
+        signature: vec![7; 64],
+        slot: 42,
+        ..Default::default()
+    }
+}
+
+/// Sends a ping and the same transaction twice, then keeps the stream open
+struct PingAndDuplicate;
+
+#[tonic::async_trait]
+impl Jetstream for PingAndDuplicate {
+    type SubscribeStream = ResponseStream<SubscribeUpdate>;
+    type SubscribeParsedStream = ResponseStream<SubscribeUpdateParsedTransaction>;
+
+    async fn subscribe(
+        &self,
+        _request: Request<Streaming<SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let updates = [ping(), transaction(), transaction()].map(Ok);
+        let stream = tokio_stream::iter(updates).chain(tokio_stream::pending());
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn subscribe_parsed(
+        &self,
+        _request: Request<Streaming<SubscribeParsedRequest>>,
+    ) -> Result<Response<Self::SubscribeParsedStream>, Status> {
+        let stream = tokio_stream::iter([Ok(parsed_transaction())]).chain(tokio_stream::pending());
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn ping(&self, _request: Request<PingRequest>) -> Result<Response<PongResponse>, Status> {
+        Err(Status::unimplemented("ping"))
+    }
+
+    async fn get_version(
+        &self,
+        _request: Request<GetVersionRequest>,
+    ) -> Result<Response<GetVersionResponse>, Status> {
+        Err(Status::unimplemented("get_version"))
+    }
+}
+
+async fn serve() -> JetstreamClient {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let server = Server::builder().add_service(JetstreamServer::new(PingAndDuplicate));
+    tokio::spawn(server.serve_with_incoming(TcpListenerStream::new(listener)));
+
+    JetstreamClient::builder()
+        .endpoint(url)
+        .reconnect(ReconnectConfig {
+            max_attempts: Some(0),
+            ..Default::default()
+        })
+        .connect_timeout(Duration::from_secs(5))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn tap_yields_messages_before_dedup() {
+    let client = serve().await;
+    let (mut stream, mut tap) = client.subscribe_with_tap(16);
+
+    for expected in [ping(), transaction(), transaction()] {
+        let raw = tokio::time::timeout(Duration::from_secs(10), tap.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(raw.endpoint, 0);
+        assert_eq!(raw.bytes.as_ref(), expected.encode_to_vec().as_slice());
+    }
+
+    let update = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let UpdateKind::Transaction(tx_info) = update.kind else {
+        panic!("expected a transaction update");
+    };
+    assert_eq!(tx_info.signature, vec![7; 64]);
+    // The duplicate is dropped from the stream
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), stream.next())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn parsed_tap_yields_transactions_as_received() {
+    let client = serve().await;
+    let (mut stream, mut tap) = client.subscribe_parsed_with_tap(16);
+
+    let raw = tokio::time::timeout(Duration::from_secs(10), tap.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(raw.slot, Some(42));
+    assert_eq!(
+        raw.bytes.as_ref(),
+        parsed_transaction().encode_to_vec().as_slice()
+    );
+
+    let parsed_tx = tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(parsed_tx, parsed_transaction());
+}
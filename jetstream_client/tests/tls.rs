@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+use jetstream_client::config::{ReconnectConfig, TlsConfig};
+use jetstream_client::{JetstreamClient, UpdateKind};
+use jetstream_protos::jetstream::jetstream_server::{Jetstream, JetstreamServer};
+use jetstream_protos::jetstream::{
+    subscribe_update::UpdateOneof, GetVersionRequest, GetVersionResponse, PingRequest,
+    PongResponse, SubscribeParsedRequest, SubscribeRequest, SubscribeUpdate,
+    SubscribeUpdateParsedTransaction, SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo,
+};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::transport::{self, Server, ServerTlsConfig};
+use tonic::{Request, Response, Status, Streaming};
+
+const SERVER_NAME: &str = "jetstream.test";
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Sends one transaction per subscription, then keeps the stream open
+struct OneTransaction;
+
+#[tonic::async_trait]
+impl Jetstream for OneTransaction {
+    type SubscribeStream = ResponseStream<SubscribeUpdate>;
+    type SubscribeParsedStream = ResponseStream<SubscribeUpdateParsedTransaction>;
+
+    async fn subscribe(
+        &self,
+        _request: Request<Streaming<SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let update = SubscribeUpdate {
+            filters: vec!["tls".to_string()],
+            created_at: None,
+            update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+                transaction: Some(SubscribeUpdateTransactionInfo {
+                    signature: vec![7; 64],
+                    slot: 42,
+                    ..Default::default()
+                }),
+                slot: 42,
+            })),
+        };
+        let stream = tokio_stream::iter([Ok(update)]).chain(tokio_stream::pending());
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn subscribe_parsed(
+        &self,
+        _request: Request<Streaming<SubscribeParsedRequest>>,
+    ) -> Result<Response<Self::SubscribeParsedStream>, Status> {
+        Err(Status::unimplemented("subscribe_parsed"))
+    }
+
+    async fn ping(&self, _request: Request<PingRequest>) -> Result<Response<PongResponse>, Status> {
+        Err(Status::unimplemented("ping"))
+    }
+
+    async fn get_version(
+        &self,
+        _request: Request<GetVersionRequest>,
+    ) -> Result<Response<GetVersionResponse>, Status> {
+        Err(Status::unimplemented("get_version"))
+    }
+}
+
+/// A self-signed CA with a server and a client certificate, written as PEM files
+struct Pki {
+    dir: PathBuf,
+    ca: Certificate,
+    ca_key: KeyPair,
+}
+
+impl Pki {
+    fn new(name: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("jetstream-tls-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let ca_key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = params.self_signed(&ca_key).unwrap();
+        std::fs::write(dir.join("ca.pem"), ca.pem()).unwrap();
+
+        Self { dir, ca, ca_key }
+    }
+
+    /// Issue a certificate for `name`, returning the certificate and key paths
+    fn issue(&self, name: &str, usage: ExtendedKeyUsagePurpose) -> (PathBuf, PathBuf) {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec![name.to_string()]).unwrap();
+        params.extended_key_usages = vec![usage];
+        let cert = params.signed_by(&key, &self.ca, &self.ca_key).unwrap();
+
+        let cert_path = self.dir.join(format!("{}.pem", name));
+        let key_path = self.dir.join(format!("{}.key", name));
+        std::fs::write(&cert_path, cert.pem()).unwrap();
+        std::fs::write(&key_path, key.serialize_pem()).unwrap();
+        (cert_path, key_path)
+    }
+
+    fn ca_path(&self) -> PathBuf {
+        self.dir.join("ca.pem")
+    }
+}
+
+impl Drop for Pki {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Serve [`OneTransaction`] over TLS, requiring client certificates when `mtls` is set
+async fn serve(pki: &Pki, mtls: bool) -> String {
+    let (cert, key) = pki.issue(SERVER_NAME, ExtendedKeyUsagePurpose::ServerAuth);
+    let identity =
+        transport::Identity::from_pem(std::fs::read(cert).unwrap(), std::fs::read(key).unwrap());
+    let mut tls = ServerTlsConfig::new().identity(identity);
+    if mtls {
+        let ca = std::fs::read(pki.ca_path()).unwrap();
+        tls = tls.client_ca_root(transport::Certificate::from_pem(ca));
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!(
+        "https://127.0.0.1:{}",
+        listener.local_addr().unwrap().port()
+    );
+    let server = Server::builder()
+        .tls_config(tls)
+        .unwrap()
+        .add_service(JetstreamServer::new(OneTransaction));
+    tokio::spawn(server.serve_with_incoming(TcpListenerStream::new(listener)));
+    url
+}
+
+/// First item of a subscription to `url` that gives up after one failed session
+async fn first_update(url: String, tls: TlsConfig) -> anyhow::Result<jetstream_client::Update> {
+    let client = JetstreamClient::builder()
+        .endpoint(url)
+        .tls(tls)
+        .reconnect(ReconnectConfig {
+            max_attempts: Some(0),
+            ..Default::default()
+        })
+        .connect_timeout(Duration::from_secs(5))
+        .build()?;
+    let mut stream = client.subscribe();
+    tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("stream ended"))?
+}
+
+fn assert_transaction(update: jetstream_client::Update) {
+    let UpdateKind::Transaction(tx_info) = update.kind else {
+        panic!("expected a transaction update");
+    };
+    assert_eq!(tx_info.signature, vec![7; 64]);
+    assert_eq!(tx_info.slot, 42);
+    assert_eq!(update.filters, ["tls"]);
+}
+
+#[tokio::test]
+async fn server_auth_with_custom_ca() {
+    let pki = Pki::new("server-auth");
+    let url = serve(&pki, false).await;
+
+    let update = first_update(
+        url,
+        TlsConfig {
+            ca_cert: Some(pki.ca_path()),
+            no_system_roots: true,
+            domain_name: Some(SERVER_NAME.to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_transaction(update);
+}
+
+#[tokio::test]
+async fn untrusted_server_is_rejected() {
+    let pki = Pki::new("untrusted");
+    let url = serve(&pki, false).await;
+
+    let result = first_update(
+        url,
+        TlsConfig {
+            no_system_roots: true,
+            domain_name: Some(SERVER_NAME.to_string()),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn mutual_tls_with_client_certificate() {
+    let pki = Pki::new("mtls");
+    let url = serve(&pki, true).await;
+    let (client_cert, client_key) = pki.issue("client", ExtendedKeyUsagePurpose::ClientAuth);
+
+    let update = first_update(
+        url,
+        TlsConfig {
+            ca_cert: Some(pki.ca_path()),
+            no_system_roots: true,
+            client_cert: Some(client_cert),
+            client_key: Some(client_key),
+            domain_name: Some(SERVER_NAME.to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    assert_transaction(update);
+}
+
+#[tokio::test]
+async fn mutual_tls_requires_client_certificate() {
+    let pki = Pki::new("mtls-missing");
+    let url = serve(&pki, true).await;
+
+    let result = first_update(
+        url,
+        TlsConfig {
+            ca_cert: Some(pki.ca_path()),
+            no_system_roots: true,
+            domain_name: Some(SERVER_NAME.to_string()),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(result.is_err());
+}